@@ -1,5 +1,13 @@
-#[deriving(Show)]
+#[deriving(Show, PartialEq)]
 pub enum GraphError {
     NodeNotFound,
     CannotRemoveNode,
+    EdgeNotFound,
+    NegativeWeight,
+    CycleDetected,
+    // Line of the input the parse failed on (0 if not tied to a line)
+    ParseError(uint),
+    DimensionMismatch,
+    NotBipartite,
+    LabelCollision,
 }
@@ -0,0 +1,722 @@
+use std::collections::{HashMap, RingBuf};
+use std::vec::Vec;
+use super::super::errors::GraphError;
+use super::graph::{Graph, Node};
+use super::dot;
+use std::fmt;
+use std::fmt::Show;
+use std::hash::Hash;
+
+pub struct DiGraph<N> {
+    nodes: Vec<N>,
+    attr_list: HashMap<uint, HashMap<String, String>>,
+    // Outgoing edges only: adj_list[from] holds (to, weight)
+    adj_list: HashMap<uint, Vec<(uint, f64)>>,
+    // The same edges the other way round: pred_list[to] holds each `from`.
+    // Kept in step with adj_list so in-edges don't need a full scan.
+    pred_list: HashMap<uint, Vec<uint>>,
+    // Maps each node to its position in `nodes`, for O(1) lookups
+    node_index: HashMap<N, uint>,
+    name: String,
+}
+
+// DiGraph over the built-in Node type
+pub type DefaultDiGraph = DiGraph<Node>;
+
+struct DiEdge(uint, uint);
+
+// Same layout as Graph, except that an edge is only stored once,
+// in the adj_list of its source node (plus pred_list, see above).
+impl<N: Eq + Hash + Clone> DiGraph<N> {
+    pub fn new() -> DiGraph<N> {
+        // Create an empty DiGraph
+        DiGraph {
+            nodes: Vec::new(),
+            attr_list: HashMap::new(),
+            adj_list: HashMap::new(),
+            pred_list: HashMap::new(),
+            node_index: HashMap::new(),
+            name: String::new(),
+        }
+    }
+
+    pub fn name(&self) -> &String {
+        // Return name of graph
+        return &self.name;
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        // Rename the graph
+        self.name = name;
+    }
+
+    pub fn node_count(&self) -> uint {
+        // Return number of nodes in graph
+        return self.nodes.len();
+    }
+
+    pub fn edge_count(&self) -> uint {
+        // Return number of directed edges in graph
+        // Each edge has exactly one entry, in its source's adj_list.
+        let mut total = 0;
+        for succs in self.adj_list.values() {
+            total += succs.len();
+        }
+        return total;
+    }
+
+    pub fn density(&self) -> f64 {
+        // Fraction of the possible edges that are present, E / (V (V - 1)).
+        // 0.0 for graphs with fewer than two nodes.
+        if self.nodes.len() < 2 {
+            return 0.0;
+        }
+        let n = self.nodes.len() as f64;
+        return self.edge_count() as f64 / (n * (n - 1.0));
+    }
+
+    pub fn add_node(&mut self, node: N) -> uint {
+        // Add a node and return its index.
+        // Adding a node that is already there changes nothing: the index
+        // it already has is returned, however often it is re-added.
+        if self.has_node(&node) {
+            return self.get_index(&node);
+        }
+
+        self.node_index.insert(node.clone(), self.nodes.len());
+        self.nodes.push(node);
+
+        let index = self.nodes.len() - 1;
+        self.adj_list.insert(index, Vec::new());
+        self.pred_list.insert(index, Vec::new());
+        self.attr_list.insert(index, HashMap::new());
+
+        return index;
+    }
+
+    pub fn add_nodes_multiple(&mut self, nodes: Vec<N>) -> Vec<uint> {
+        // Add several nodes at once.
+        // Returns the index of each given node, in order.
+        let mut node_indices = Vec::<uint>::new();
+        for node in nodes.into_iter() {
+            node_indices.push(self.add_node(node));
+        }
+        return node_indices;
+    }
+
+    pub fn set_node_attr(&mut self, node: &N,
+                         node_attr: HashMap<String, String>)
+                         -> Result<(), GraphError> {
+        // Replace the whole attribute map of a node
+        if !self.has_node(node) {
+            return Err(GraphError::NodeNotFound);
+        }
+        let index = self.get_index(node);
+        self.attr_list.insert(index, node_attr);
+        return Ok(());
+    }
+
+    pub fn add_edge(&mut self, from: &N, to: &N) {
+        // Add a directed edge from -> to, with the default weight 1.0
+        // Nodes may or may not be already added. An edge that's already
+        // there is left alone, weight and all.
+        if self.has_node(from) && self.has_node(to) {
+            let to_index = self.get_index(to);
+            if self.adj_list[self.get_index(from)].iter()
+                   .any(|&(succ, _)| succ == to_index) {
+                return;
+            }
+        }
+        self.add_weighted_edge(from, to, 1.0);
+    }
+
+    pub fn add_weighted_edge(&mut self, from: &N, to: &N,
+                             weight: f64) {
+        // Add a directed edge from -> to of the given weight
+        // Nodes may or may not be already added. If the edge is already
+        // there, its weight is replaced.
+        if !self.has_node(from) {
+            self.add_node(from.clone());
+        }
+        if !self.has_node(to) {
+            self.add_node(to.clone());
+        }
+        let from_index = self.get_index(from);
+        let to_index = self.get_index(to);
+
+        // If the edge is already present, just update its weight
+        for entry in self.adj_list[from_index].iter_mut() {
+            let (succ, _) = *entry;
+            if succ == to_index {
+                *entry = (succ, weight);
+                return;
+            }
+        }
+
+        // The edge lives in the source's adj_list; the target only gets
+        // a back-reference in pred_list
+        self.adj_list[from_index].push((to_index, weight));
+        self.pred_list[to_index].push(from_index);
+    }
+
+    pub fn remove_edge(&mut self, from: &N, to: &N)
+                       -> Result<(), GraphError> {
+        // Remove the directed edge from -> to
+        if !self.has_node(from) || !self.has_node(to) {
+            return Err(GraphError::NodeNotFound);
+        }
+
+        let from_index = self.get_index(from);
+        let to_index = self.get_index(to);
+        match self.adj_list[from_index].iter()
+                  .position(|&(n, _)| n == to_index) {
+            Some(pos) => { self.adj_list[from_index].remove(pos); }
+            None => { return Err(GraphError::EdgeNotFound); }
+        };
+        let pos = self.pred_list[to_index].iter()
+                      .position(|&n| n == from_index).unwrap();
+        self.pred_list[to_index].remove(pos);
+        return Ok(());
+    }
+
+    pub fn successors(&self, node: &N) -> Result<Vec<&N>, GraphError> {
+        // Nodes that `node` has an edge to, in the order the edges were added
+        if !self.has_node(node) {
+            return Err(GraphError::NodeNotFound);
+        }
+
+        let mut succs = Vec::<&N>::new();
+        for &(succ, _) in self.adj_list[self.get_index(node)].iter() {
+            succs.push(&self.nodes[succ]);
+        }
+        return Ok(succs);
+    }
+
+    pub fn predecessors(&self, node: &N)
+                        -> Result<Vec<&N>, GraphError> {
+        // Nodes that have an edge to `node`, in the order the edges were added
+        if !self.has_node(node) {
+            return Err(GraphError::NodeNotFound);
+        }
+
+        let mut preds = Vec::<&N>::new();
+        for &pred in self.pred_list[self.get_index(node)].iter() {
+            preds.push(&self.nodes[pred]);
+        }
+        return Ok(preds);
+    }
+
+    pub fn in_degree(&self, node: &N) -> Result<uint, GraphError> {
+        // Number of edges coming into a node
+        if !self.has_node(node) {
+            return Err(GraphError::NodeNotFound);
+        }
+        return Ok(self.pred_list[self.get_index(node)].len());
+    }
+
+    pub fn out_degree(&self, node: &N) -> Result<uint, GraphError> {
+        // Number of edges going out of a node
+        if !self.has_node(node) {
+            return Err(GraphError::NodeNotFound);
+        }
+        return Ok(self.adj_list[self.get_index(node)].len());
+    }
+
+    pub fn topological_sort(&self) -> Result<Vec<&N>, GraphError> {
+        // Order the nodes so that every edge points forward, using
+        // Kahn's algorithm. Fails with CycleDetected if no such order exists.
+        let mut in_degree = Vec::from_elem(self.nodes.len(), 0u);
+        for index in range(0, self.nodes.len()) {
+            for &(succ, _) in self.adj_list[index].iter() {
+                in_degree[succ] += 1;
+            }
+        }
+
+        // Start from the nodes that nothing points to
+        let mut queue = RingBuf::<uint>::new();
+        for index in range(0, self.nodes.len()) {
+            if in_degree[index] == 0 {
+                queue.push_back(index);
+            }
+        }
+
+        let mut order = Vec::<&N>::new();
+        loop {
+            let current = match queue.pop_front() {
+                Some(x) => x,
+                None => break,
+            };
+            order.push(&self.nodes[current]);
+
+            for &(succ, _) in self.adj_list[current].iter() {
+                in_degree[succ] -= 1;
+                if in_degree[succ] == 0 {
+                    queue.push_back(succ);
+                }
+            }
+        }
+
+        // Nodes on a cycle never get down to zero in-degree
+        if order.len() < self.nodes.len() {
+            return Err(GraphError::CycleDetected);
+        }
+        return Ok(order);
+    }
+
+    pub fn to_undirected(&self) -> Graph<N> {
+        // Graph with the same nodes (and node attributes) where nodes are
+        // adjacent if there is an edge either way between them. When both
+        // directions exist, the weight of the edge out of the lower-indexed
+        // node is kept. Self-loops are kept too, as is the name.
+        let mut graph = Graph::new();
+        graph.set_name(self.name.clone());
+        for (index, node) in self.nodes.iter().enumerate() {
+            graph.add_node(node.clone());
+            let _ = graph.set_node_attr(node, self.attr_list[index].clone());
+        }
+        // Loops are only let through while copying; the new graph still
+        // has the default setting afterwards
+        graph.allow_self_loops(true);
+        for index in range(0, self.nodes.len()) {
+            for &(succ, weight) in self.adj_list[index].iter() {
+                let from = &self.nodes[index];
+                let to = &self.nodes[succ];
+                if !graph.contains_edge(from, to) {
+                    graph.add_weighted_edge(from, to, weight);
+                }
+            }
+        }
+        graph.allow_self_loops(false);
+        return graph;
+    }
+
+    pub fn transpose(&self) -> DiGraph<N> {
+        // New DiGraph with every edge reversed. Nodes keep their indices,
+        // and the name, attributes and weights are copied across.
+        let mut graph = DiGraph::new();
+        graph.name = self.name.clone();
+        for (index, node) in self.nodes.iter().enumerate() {
+            graph.add_node(node.clone());
+            graph.attr_list.insert(index, self.attr_list[index].clone());
+        }
+        for index in range(0, self.nodes.len()) {
+            for &(succ, weight) in self.adj_list[index].iter() {
+                graph.adj_list[succ].push((index, weight));
+                graph.pred_list[index].push(succ);
+            }
+        }
+        return graph;
+    }
+
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&N>> {
+        // Split the graph into groups of nodes that can all reach each
+        // other, using Tarjan's algorithm. A component comes out only after
+        // every component it has edges into, and within a component nodes
+        // are in the order they were first visited.
+        let n = self.nodes.len();
+        let mut order: Vec<Option<uint>> = Vec::from_elem(n, None);
+        let mut low = Vec::from_elem(n, 0u);
+        let mut on_stack = Vec::from_elem(n, false);
+        let mut stack = Vec::<uint>::new();
+        let mut counter = 0u;
+        let mut components = Vec::<Vec<&N>>::new();
+
+        for root in range(0, n) {
+            if order[root].is_some() {
+                continue;
+            }
+            order[root] = Some(counter);
+            low[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            // Explicit DFS stack of (node, position of next successor)
+            let mut work = vec![(root, 0u)];
+            loop {
+                let (current, pos) = match work.pop() {
+                    Some(x) => x,
+                    None => break,
+                };
+
+                if pos < self.adj_list[current].len() {
+                    work.push((current, pos + 1));
+                    let (succ, _) = self.adj_list[current][pos];
+                    match order[succ] {
+                        None => {
+                            order[succ] = Some(counter);
+                            low[succ] = counter;
+                            counter += 1;
+                            stack.push(succ);
+                            on_stack[succ] = true;
+                            work.push((succ, 0));
+                        }
+                        Some(succ_order) if on_stack[succ] => {
+                            if succ_order < low[current] {
+                                low[current] = succ_order;
+                            }
+                        }
+                        _ => {}
+                    };
+                    continue;
+                }
+
+                // All successors done. A node that can't reach anything
+                // earlier on the stack is the root of a component.
+                if Some(low[current]) == order[current] {
+                    let mut component = Vec::<&N>::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(&self.nodes[member]);
+                        if member == current {
+                            break;
+                        }
+                    }
+                    component.reverse();
+                    components.push(component);
+                }
+                match work.last() {
+                    Some(&(parent, _)) if low[current] < low[parent] => {
+                        low[parent] = low[current];
+                    }
+                    _ => {}
+                };
+            }
+        }
+        return components;
+    }
+
+    pub fn weakly_connected_components(&self) -> Vec<Vec<&N>> {
+        // Split the graph into its components when edge directions are
+        // ignored. Components come out in the order of their first inserted
+        // node, each in BFS order, as with Graph::connected_components.
+        let n = self.nodes.len();
+        let mut visited = Vec::from_elem(n, false);
+        let mut components = Vec::<Vec<&N>>::new();
+        for root in range(0, n) {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut component = Vec::<&N>::new();
+            let mut queue = RingBuf::<uint>::new();
+            queue.push_back(root);
+
+            loop {
+                let current = match queue.pop_front() {
+                    Some(x) => x,
+                    None => break,
+                };
+                component.push(&self.nodes[current]);
+
+                // Follow edges both ways
+                let mut nbrs = Vec::<uint>::new();
+                for &(succ, _) in self.adj_list[current].iter() {
+                    nbrs.push(succ);
+                }
+                nbrs.push_all(self.pred_list[current].as_slice());
+                for &nbr in nbrs.iter() {
+                    if !visited[nbr] {
+                        visited[nbr] = true;
+                        queue.push_back(nbr);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        return components;
+    }
+
+    pub fn pagerank(&self, damping: f64, iterations: uint)
+                    -> HashMap<uint, f64> {
+        // PageRank of every node, keyed by index, by power iteration.
+        // Each round a node keeps (1 - damping) / N and passes `damping` of
+        // its rank evenly along its out-edges. Nodes without out-edges
+        // spread theirs over all nodes. Ranks always sum to 1.
+        let n = self.nodes.len();
+        let mut rank = Vec::from_elem(n, 1.0 / n as f64);
+
+        for _ in range(0, iterations) {
+            let mut dangling = 0.0;
+            for index in range(0, n) {
+                if self.adj_list[index].is_empty() {
+                    dangling += rank[index];
+                }
+            }
+
+            let base = (1.0 - damping + damping * dangling) / n as f64;
+            let mut next = Vec::from_elem(n, base);
+            for index in range(0, n) {
+                let succs = &self.adj_list[index];
+                if succs.is_empty() {
+                    continue;
+                }
+                let share = damping * rank[index] / succs.len() as f64;
+                for &(succ, _) in succs.iter() {
+                    next[succ] += share;
+                }
+            }
+            rank = next;
+        }
+
+        let mut ranks = HashMap::<uint, f64>::new();
+        for index in range(0, n) {
+            ranks.insert(index, rank[index]);
+        }
+        return ranks;
+    }
+
+    // Helpers from here on out
+    // To be used internally only. No public API.
+
+    fn edges(&self) -> Vec<DiEdge> {
+        // Return all edges of a DiGraph, grouped by source node
+        let mut edge_vec = Vec::<DiEdge>::new();
+        for index in range(0, self.nodes.len()) {
+            for &(succ, _) in self.adj_list[index].iter() {
+                edge_vec.push(DiEdge(index, succ));
+            }
+        }
+        return edge_vec;
+    }
+
+    fn get_index(&self, node: &N) -> uint {
+        // Same as Graph::get_index. Panics for unknown nodes.
+        match self.node_index.get(node) {
+            Some(&index) => { return index; }
+            None => { panic!("Node does not exist."); }
+        };
+    }
+
+    fn has_node(&self, node: &N) -> bool {
+        return self.node_index.contains_key(node);
+    }
+}
+
+// Methods that need the built-in Node type, as with Graph
+impl DiGraph<Node> {
+    pub fn to_dot(&self) -> String {
+        // Return a GraphViz (DOT) description of the DiGraph
+        // Node attributes are written out as [key="value", ...]
+        // A named graph is written as `digraph "name" {`.
+        let mut edges = Vec::<(uint, uint)>::new();
+        for edge in self.edges().iter() {
+            let DiEdge(from, to) = *edge;
+            edges.push((from, to));
+        }
+        return dot::write_graph("digraph", self.name.as_slice(),
+                                self.nodes.as_slice(), &self.attr_list,
+                                edges.as_slice());
+    }
+}
+
+
+impl<N: Eq + Hash + Clone + Show> Show for DiGraph<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Shows textual repr of DiGraph
+        try!(write!(f, "{{ Nodes: "));
+        for n in self.nodes.iter() {
+            try!(write!(f, "{}, ", n));
+        }
+        try!(writeln!(f, ""));
+        try!(write!(f, "Edges: "));
+        for edge in self.edges().iter() {
+            try!(write!(f, "{}, ", edge));
+        }
+        write!(f, "}}")
+    }
+}
+
+impl Show for DiEdge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let DiEdge(from, to) = *self;
+        write!(f, "{}->{}", from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::Float;
+    use super::DiGraph;
+    use super::super::graph::Node;
+    use super::super::super::errors::GraphError;
+
+    fn s(label: &str) -> Node {
+        Node::Str(label.to_string())
+    }
+
+    fn digraph_of(edges: &[(&str, &str)]) -> DiGraph<Node> {
+        // Str-labelled DiGraph with the given edges, added in order
+        let mut graph = DiGraph::new();
+        for &(from, to) in edges.iter() {
+            graph.add_edge(&s(from), &s(to));
+        }
+        return graph;
+    }
+
+    #[test]
+    fn edge_only_goes_one_way() {
+        let graph = digraph_of(&[("a", "b")]);
+        assert_eq!(graph.successors(&s("a")).unwrap(), vec![&s("b")]);
+        assert!(graph.successors(&s("b")).unwrap().is_empty());
+        assert_eq!(graph.predecessors(&s("b")).unwrap(), vec![&s("a")]);
+        assert!(graph.predecessors(&s("a")).unwrap().is_empty());
+        assert_eq!(graph.successors(&s("z")), Err(GraphError::NodeNotFound));
+        assert_eq!(graph.edge_count(), 1);
+    }
+
+    #[test]
+    fn topological_sort_respects_every_edge() {
+        // Several valid orders: b and c can come either way round
+        let edges = [("a", "b"), ("a", "c"), ("b", "d"), ("c", "d"),
+                     ("d", "e")];
+        let graph = digraph_of(&edges);
+        let order = graph.topological_sort().unwrap();
+        assert_eq!(order.len(), 5);
+        for &(from, to) in edges.iter() {
+            let from_pos = order.iter().position(|n| **n == s(from)).unwrap();
+            let to_pos = order.iter().position(|n| **n == s(to)).unwrap();
+            assert!(from_pos < to_pos);
+        }
+    }
+
+    #[test]
+    fn topological_sort_of_cycle() {
+        let graph = digraph_of(&[("a", "b"), ("b", "c"), ("c", "a")]);
+        assert_eq!(graph.topological_sort(), Err(GraphError::CycleDetected));
+    }
+
+    #[test]
+    fn to_dot_uses_arrows() {
+        let graph = digraph_of(&[("a", "b"), ("b", "a"), ("b", "c")]);
+        assert_eq!(graph.to_dot(),
+                   "digraph {\n    \"a\";\n    \"b\";\n    \"c\";\n    \"a\" -> \"b\";\n    \"b\" -> \"a\";\n    \"b\" -> \"c\";\n}\n".to_string());
+    }
+
+    #[test]
+    fn density_counts_each_direction() {
+        // 3 of the 6 possible directed edges
+        let graph = digraph_of(&[("a", "b"), ("b", "a"), ("b", "c")]);
+        assert_eq!(graph.density(), 0.5);
+        assert_eq!(DiGraph::<Node>::new().density(), 0.0);
+    }
+
+    #[test]
+    fn pagerank_of_cycle_is_uniform() {
+        let graph = digraph_of(&[("a", "b"), ("b", "c"), ("c", "d"),
+                                 ("d", "a")]);
+        let ranks = graph.pagerank(0.85, 50);
+        assert_eq!(ranks.len(), 4);
+        for index in range(0u, 4) {
+            assert!((ranks[index] - 0.25).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn pagerank_redistributes_dangling_rank() {
+        // Everything points at c, which has no out-edges
+        let graph = digraph_of(&[("a", "c"), ("b", "c")]);
+        let ranks = graph.pagerank(0.85, 100);
+        let total = ranks.values().fold(0.0, |sum, &r| sum + r);
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!(ranks[2] > ranks[0]);
+        assert!((ranks[0] - ranks[1]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn transpose_reverses_path() {
+        let mut graph = digraph_of(&[("a", "b"), ("b", "c")]);
+        graph.add_weighted_edge(&s("a"), &s("b"), 2.0);
+        let reversed = graph.transpose();
+        assert_eq!(reversed.successors(&s("c")).unwrap(), vec![&s("b")]);
+        assert_eq!(reversed.successors(&s("b")).unwrap(), vec![&s("a")]);
+        assert!(reversed.successors(&s("a")).unwrap().is_empty());
+        assert_eq!(reversed.predecessors(&s("a")).unwrap(), vec![&s("b")]);
+        assert_eq!(reversed.topological_sort().unwrap(),
+                   vec![&s("c"), &s("b"), &s("a")]);
+        assert_eq!(reversed.adj_list[1], vec![(0, 2.0)]);
+        // The original is untouched
+        assert_eq!(graph.successors(&s("a")).unwrap(), vec![&s("b")]);
+    }
+
+    #[test]
+    fn strongly_connected_cycle_with_tail() {
+        // a -> b -> c -> a, then c -> d -> e
+        let graph = digraph_of(&[("a", "b"), ("b", "c"), ("c", "a"),
+                                 ("c", "d"), ("d", "e")]);
+        let components = graph.strongly_connected_components();
+        assert_eq!(components, vec![vec![&s("e")], vec![&s("d")],
+                                    vec![&s("a"), &s("b"), &s("c")]]);
+    }
+
+    #[test]
+    fn strongly_connected_dag_is_all_singletons() {
+        let graph = digraph_of(&[("a", "b"), ("a", "c"), ("b", "c")]);
+        let components = graph.strongly_connected_components();
+        assert_eq!(components.len(), 3);
+        for component in components.iter() {
+            assert_eq!(component.len(), 1);
+        }
+    }
+
+    #[test]
+    fn opposite_stars_are_weakly_one_component() {
+        // a points out at b and c; d has b and c pointing in at it
+        let mut graph = digraph_of(&[("a", "b"), ("a", "c"), ("b", "d"),
+                                     ("c", "d")]);
+        graph.add_edge(&s("x"), &s("y"));
+        let components = graph.weakly_connected_components();
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0], vec![&s("a"), &s("b"), &s("c"), &s("d")]);
+        assert_eq!(components[1], vec![&s("x"), &s("y")]);
+        // ...though no two of a, b, c, d are strongly connected
+        assert_eq!(graph.strongly_connected_components().len(), 6);
+    }
+
+    #[test]
+    fn in_and_out_degrees() {
+        let graph = digraph_of(&[("a", "b"), ("c", "b")]);
+        assert_eq!(graph.in_degree(&s("b")), Ok(2));
+        assert_eq!(graph.out_degree(&s("b")), Ok(0));
+        assert_eq!(graph.in_degree(&s("a")), Ok(0));
+        assert_eq!(graph.out_degree(&s("a")), Ok(1));
+        assert_eq!(graph.in_degree(&s("z")), Err(GraphError::NodeNotFound));
+        assert_eq!(graph.out_degree(&s("z")), Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn in_degree_follows_remove_edge() {
+        let mut graph = digraph_of(&[("a", "b"), ("c", "b")]);
+        graph.remove_edge(&s("a"), &s("b")).unwrap();
+        assert_eq!(graph.in_degree(&s("b")), Ok(1));
+        assert_eq!(graph.predecessors(&s("b")).unwrap(), vec![&s("c")]);
+        assert_eq!(graph.remove_edge(&s("a"), &s("b")),
+                   Err(GraphError::EdgeNotFound));
+    }
+
+    #[test]
+    fn to_undirected_collapses_both_directions() {
+        let mut graph = digraph_of(&[("a", "b"), ("b", "a"), ("b", "c")]);
+        graph.add_edge(&s("c"), &s("c"));
+        let undirected = graph.to_undirected();
+        assert_eq!(undirected.node_count(), 3);
+        assert_eq!(undirected.edge_count(), 3);
+        assert!(undirected.contains_edge(&s("a"), &s("b")));
+        assert!(undirected.contains_edge(&s("c"), &s("b")));
+        assert_eq!(undirected.self_loops(), vec![&s("c")]);
+
+        // The loop came across, but the option is still off
+        let mut undirected = undirected;
+        undirected.add_edge(&s("a"), &s("a"));
+        assert_eq!(undirected.self_loops(), vec![&s("c")]);
+    }
+
+    #[test]
+    fn to_dot_writes_name() {
+        let mut graph = digraph_of(&[("a", "b")]);
+        graph.set_name("flow \"chart\"".to_string());
+        assert!(graph.to_dot().as_slice()
+                     .starts_with("digraph \"flow \\\"chart\\\"\" {\n"));
+    }
+}
@@ -0,0 +1,64 @@
+// GraphViz (DOT) output shared by Graph and DiGraph, plus the node labels
+// the other text formats use too.
+
+use std::collections::HashMap;
+use super::graph::Node;
+
+pub fn write_graph(kind: &str, name: &str, nodes: &[Node],
+                   attr_list: &HashMap<uint, HashMap<String, String>>,
+                   edges: &[(uint, uint)]) -> String {
+    // Write out a whole graph. `kind` is "graph" or "digraph", which also
+    // picks the edge operator. A named graph is written as `kind "name" {`.
+    // Node attributes come out as [key="value", ...], sorted by key.
+    let edge_op = if kind == "digraph" { "->" } else { "--" };
+    let mut dot = String::from_str(kind);
+    dot.push_str(" ");
+    if !name.is_empty() {
+        dot.push_str(quote(name).as_slice());
+        dot.push_str(" ");
+    }
+    dot.push_str("{\n");
+
+    for (index, node) in nodes.iter().enumerate() {
+        dot.push_str(format!("    {}",
+                             quote(extract_node(node).as_slice())).as_slice());
+
+        let attrs = &attr_list[index];
+        if !attrs.is_empty() {
+            let mut sorted: Vec<(&String, &String)> = attrs.iter().collect();
+            sorted.sort();
+            let mut pairs = Vec::<String>::new();
+            for &(key, value) in sorted.iter() {
+                pairs.push(format!("{}={}", quote(key.as_slice()),
+                                   quote(value.as_slice())));
+            }
+            dot.push_str(format!(" [{}]", pairs.connect(", ")).as_slice());
+        }
+        dot.push_str(";\n");
+    }
+
+    for &(node1, node2) in edges.iter() {
+        let label1 = extract_node(&nodes[node1]);
+        let label2 = extract_node(&nodes[node2]);
+        dot.push_str(format!("    {} {} {};\n", quote(label1.as_slice()),
+                             edge_op, quote(label2.as_slice())).as_slice());
+    }
+
+    dot.push_str("}\n");
+    return dot;
+}
+
+pub fn extract_node(node: &Node) -> String {
+    // Label of a node: a Str as it is, an Int in decimal
+    let node_name = match *node {
+        Node::Str(ref s) => s.clone(),
+        Node::Int(v) => v.to_string()
+    };
+    return node_name;
+}
+
+fn quote(s: &str) -> String {
+    // Quote a DOT identifier. Backslashes are escaped first, so that one
+    // at the end of a label can't swallow the closing quote.
+    return format!("\"{}\"", s.replace("\\", "\\\\").replace("\"", "\\\""));
+}
@@ -0,0 +1,183 @@
+use super::graph::{Graph, Node};
+
+// Small seeded PRNG (SplitMix64), so generated graphs can be reproduced
+// without pulling in a random number crate. Not for anything that needs
+// real randomness.
+struct SplitMix64 {
+    state: u64,
+}
+
+pub fn erdos_renyi(n: uint, p: f64, seed: u64) -> Graph<Node> {
+    // Random graph G(n, p): nodes Int(0) to Int(n - 1), each pair of
+    // distinct nodes joined with probability p.
+    // The same seed always gives the same graph.
+    let mut rng = SplitMix64 { state: seed };
+    let mut graph = Graph::new();
+    graph.add_nodes_multiple(int_nodes(n));
+    for i in range(0, n) {
+        for j in range(i + 1, n) {
+            if rng.next_f64() < p {
+                graph.add_edge(&Node::Int(i as int), &Node::Int(j as int));
+            }
+        }
+    }
+    return graph;
+}
+
+pub fn complete_graph(n: uint) -> Graph<Node> {
+    // K_n: nodes Int(0) to Int(n - 1), every pair of them joined
+    let mut graph = Graph::new();
+    graph.add_nodes_multiple(int_nodes(n));
+    for i in range(0, n) {
+        for j in range(i + 1, n) {
+            graph.add_edge(&Node::Int(i as int), &Node::Int(j as int));
+        }
+    }
+    return graph;
+}
+
+pub fn path_graph(n: uint) -> Graph<Node> {
+    // Path Int(0) - Int(1) - ... - Int(n - 1), with n - 1 edges
+    let mut graph = Graph::new();
+    graph.add_nodes_multiple(int_nodes(n));
+    for i in range(1, n) {
+        graph.add_edge(&Node::Int((i - 1) as int), &Node::Int(i as int));
+    }
+    return graph;
+}
+
+pub fn cycle_graph(n: uint) -> Graph<Node> {
+    // The path on n nodes, plus an edge from Int(n - 1) back to Int(0).
+    // Below 3 nodes that edge would be a self-loop or a repeat, so the
+    // result is just the path.
+    let mut graph = path_graph(n);
+    if n >= 3 {
+        graph.add_edge(&Node::Int((n - 1) as int), &Node::Int(0));
+    }
+    return graph;
+}
+
+pub fn grid_2d(rows: uint, cols: uint) -> Graph<Node> {
+    // rows x cols lattice, each cell joined to the cells above, below, left
+    // and right of it. The cell at (row, col) is Int(row * cols + col).
+    let mut graph = Graph::new();
+    graph.add_nodes_multiple(int_nodes(rows * cols));
+    for row in range(0, rows) {
+        for col in range(0, cols) {
+            let cell = Node::Int((row * cols + col) as int);
+            if col + 1 < cols {
+                let right = Node::Int((row * cols + col + 1) as int);
+                graph.add_edge(&cell, &right);
+            }
+            if row + 1 < rows {
+                let below = Node::Int(((row + 1) * cols + col) as int);
+                graph.add_edge(&cell, &below);
+            }
+        }
+    }
+    return graph;
+}
+
+
+fn int_nodes(n: uint) -> Vec<Node> {
+    // Nodes Int(0) to Int(n - 1)
+    let mut nodes = Vec::<Node>::new();
+    for i in range(0, n) {
+        nodes.push(Node::Int(i as int));
+    }
+    return nodes;
+}
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state += 0x9E3779B97F4A7C15;
+        let mut z = self.state;
+        z = (z ^ (z >> 30)) * 0xBF58476D1CE4E5B9;
+        z = (z ^ (z >> 27)) * 0x94D049BB133111EB;
+        return z ^ (z >> 31);
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        // Uniform in [0, 1), from the top 53 bits
+        return (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{erdos_renyi, complete_graph, path_graph, cycle_graph,
+                grid_2d};
+    use super::super::graph::Node;
+
+    #[test]
+    fn erdos_renyi_is_reproducible() {
+        let graph = erdos_renyi(20, 0.3, 42);
+        assert_eq!(graph.node_count(), 20);
+        assert_eq!(graph.edge_count(), 58);
+        assert!(graph == erdos_renyi(20, 0.3, 42));
+        assert_eq!(erdos_renyi(20, 0.3, 7).edge_count(), 50);
+        assert!(graph.get_node_attr(&Node::Int(19)).is_ok());
+    }
+
+    #[test]
+    fn erdos_renyi_extreme_probabilities() {
+        assert_eq!(erdos_renyi(10, 0.0, 1).edge_count(), 0);
+        assert_eq!(erdos_renyi(10, 1.0, 1).edge_count(), 45);
+        assert_eq!(erdos_renyi(0, 0.5, 1).node_count(), 0);
+        assert!(erdos_renyi(10, 1.0, 1).self_loops().is_empty());
+    }
+
+    #[test]
+    fn complete_graph_on_five_nodes() {
+        let graph = complete_graph(5);
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(graph.edge_count(), 5 * 4 / 2);
+        assert_eq!(graph.degree_sequence(), vec![4, 4, 4, 4, 4]);
+        assert_eq!(complete_graph(1).edge_count(), 0);
+        assert_eq!(complete_graph(0).node_count(), 0);
+    }
+
+    #[test]
+    fn cycle_graph_has_n_edges() {
+        let graph = cycle_graph(6);
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 6);
+        assert!(graph.contains_edge(&Node::Int(5), &Node::Int(0)));
+        assert_eq!(graph.degree_sequence(), vec![2, 2, 2, 2, 2, 2]);
+        // Too small for the closing edge
+        assert_eq!(cycle_graph(2).edge_count(), 1);
+        assert!(cycle_graph(1).self_loops().is_empty());
+    }
+
+    #[test]
+    fn path_graph_has_n_minus_one_edges() {
+        let graph = path_graph(6);
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 5);
+        assert!(!graph.contains_edge(&Node::Int(5), &Node::Int(0)));
+        assert!(graph.is_tree());
+        assert_eq!(path_graph(0).node_count(), 0);
+    }
+
+    #[test]
+    fn grid_degrees() {
+        let graph = grid_2d(3, 3);
+        assert_eq!(graph.node_count(), 9);
+        assert_eq!(graph.edge_count(), 12);
+        for &corner in [0i, 2, 6, 8].iter() {
+            assert_eq!(graph.degree(&Node::Int(corner)), Ok(2));
+        }
+        for &side in [1i, 3, 5, 7].iter() {
+            assert_eq!(graph.degree(&Node::Int(side)), Ok(3));
+        }
+        assert_eq!(graph.degree(&Node::Int(4)), Ok(4));
+        // (1, 2) is joined to (0, 2) and (2, 2), not wrapped round to (2, 0)
+        assert!(graph.contains_edge(&Node::Int(5), &Node::Int(2)));
+        assert!(!graph.contains_edge(&Node::Int(5), &Node::Int(6)));
+    }
+
+    #[test]
+    fn single_row_grid_is_path() {
+        assert!(grid_2d(1, 4) == path_graph(4));
+    }
+}
@@ -1,14 +1,34 @@
-use std::collections::HashMap;
+use std::collections::{BinaryHeap, HashMap, HashSet, RingBuf};
+use std::cmp::Ordering;
 use std::vec::Vec;
+use std::slice::Items;
+use std::vec::MoveItems;
 use super::super::errors::GraphError;
+use super::digraph::DiGraph;
 use std::fmt;
 use std::fmt::Show;
+use std::hash::Hash;
+use std::iter::FromIterator;
+use std::num::Float;
+use serialize::json;
+use serialize::json::Json;
+use super::xml;
+use super::xml::Element;
+use super::dot;
+use super::dot::extract_node;
 
-pub struct Graph {
-    nodes: Vec<Node>,
+pub struct Graph<N> {
+    nodes: Vec<N>,
     attr_list: HashMap<uint, HashMap<String, String>>,
-    adj_list: HashMap<uint, Vec<uint>>,
+    // Each adjacency entry is (neighbor index, edge weight)
+    adj_list: HashMap<uint, Vec<(uint, f64)>>,
+    // Maps each node to its position in `nodes`, for O(1) lookups
+    node_index: HashMap<N, uint>,
+    // Keyed on the (lower, higher) index pair of the edge's two nodes
+    edge_attr_list: HashMap<(uint, uint), HashMap<String, String>>,
     name: String,
+    // Whether add_edge may join a node to itself. Off by default.
+    allow_self_loops: bool,
 }
 
 #[deriving(Eq, PartialEq, Hash, Clone, Show)]
@@ -17,63 +37,215 @@ pub enum Node {
     Int(int),
 }
 
+// Graph over the built-in Node type
+pub type DefaultGraph = Graph<Node>;
+
 struct Edge(uint, uint);
 
+// Iterator over the nodes of a Graph, in insertion order
+pub struct NodeIter<'a, N: 'a> {
+    iter: Items<'a, N>,
+}
+
+// Iterator over the edges of a Graph, yielding each edge once
+pub struct EdgeIter<'a, N: 'a> {
+    nodes: &'a Vec<N>,
+    edges: MoveItems<(uint, uint, f64)>,
+}
+
+// Entry in the priority queue used by dijkstra. BinaryHeap is a max-heap,
+// so the ordering on `cost` is reversed to pop the cheapest entry first.
+#[deriving(PartialEq)]
+struct HeapEntry {
+    cost: f64,
+    index: uint,
+}
+
 // Note that changing the nodes Vec physically in any way
 // must be accompanied by managing the raw pointers within
 // the adj_list of the Graph.
-impl Graph {
-    pub fn new() -> Graph {
+impl<N: Eq + Hash + Clone> Graph<N> {
+    pub fn new() -> Graph<N> {
         // Create an empty Graph
         Graph {
             nodes: Vec::new(),
             attr_list: HashMap::new(),
             adj_list: HashMap::new(),
+            node_index: HashMap::new(),
+            edge_attr_list: HashMap::new(),
+            name: String::new(),
+            allow_self_loops: false,
+        }
+    }
+
+    pub fn with_capacity(nodes: uint, edges: uint) -> Graph<N> {
+        // Create an empty Graph with room for about `nodes` nodes and
+        // `edges` edges. Only a hint: the graph still grows past it.
+        Graph {
+            nodes: Vec::with_capacity(nodes),
+            attr_list: HashMap::with_capacity(nodes),
+            adj_list: HashMap::with_capacity(nodes),
+            node_index: HashMap::with_capacity(nodes),
+            edge_attr_list: HashMap::with_capacity(edges),
             name: String::new(),
+            allow_self_loops: false,
         }
     }
 
+    pub fn with_name(name: String) -> Graph<N> {
+        // Create an empty Graph with the given name
+        let mut graph = Graph::new();
+        graph.name = name;
+        return graph;
+    }
+
     pub fn name(&self) -> &String {
         // Return name of graph
         return &self.name;
     }
 
-    pub fn add_node(&mut self, node: Node) -> &Node {
+    pub fn set_name(&mut self, name: String) {
+        // Rename the graph
+        self.name = name;
+    }
+
+    pub fn nodes_iter(&self) -> NodeIter<N> {
+        // Iterate over the nodes in the order they were added
+        NodeIter { iter: self.nodes.iter() }
+    }
+
+    pub fn edges_iter(&self) -> EdgeIter<N> {
+        // Iterate over the edges as pairs of node references.
+        // Each undirected edge comes up once, lower-indexed node first.
+        EdgeIter {
+            nodes: &self.nodes,
+            edges: self.weighted_edges().into_iter(),
+        }
+    }
+
+    pub fn node_count(&self) -> uint {
+        // Return number of nodes in graph
+        return self.nodes.len();
+    }
+
+    pub fn edge_count(&self) -> uint {
+        // Return number of (undirected) edges in graph
+        // Every edge has two entries in the adj_list - including self-loops,
+        // which push the node into its own Vec twice - so halve the total.
+        let mut total = 0;
+        for nbrs in self.adj_list.values() {
+            total += nbrs.len();
+        }
+        return total / 2;
+    }
+
+    pub fn density(&self) -> f64 {
+        // Fraction of the possible edges that are present, 2E / (V (V - 1)).
+        // 0.0 for graphs with fewer than two nodes.
+        if self.nodes.len() < 2 {
+            return 0.0;
+        }
+        let n = self.nodes.len() as f64;
+        return 2.0 * self.edge_count() as f64 / (n * (n - 1.0));
+    }
+
+    pub fn add_node(&mut self, node: N) -> uint {
+        // Add a node and return its index.
+        // Adding a node that is already there changes nothing: the index
+        // it already has is returned, however often it is re-added.
         if self.has_node(&node) {
-            return self.existing_node(&node);
+            return self.get_index(&node);
         }
 
+        self.node_index.insert(node.clone(), self.nodes.len());
         self.nodes.push(node);
-        let node_ref = self.last_node();
 
         // internally Vec::len returns self.len (struct field)
-        self.adj_list.insert(self.nodes.len(), Vec::new());
-        self.attr_list.insert(self.nodes.len(), HashMap::new());
+        // The node just pushed sits at len - 1, so key the maps on that.
+        let index = self.nodes.len() - 1;
+        self.adj_list.insert(index, Vec::new());
+        self.attr_list.insert(index, HashMap::new());
 
-        return node_ref;
+        return index;
     }
 
-    pub fn add_nodes_multiple(&mut self, nodes: Vec<Node>) -> Vec<&Node> {
+    pub fn add_nodes_multiple(&mut self, nodes: Vec<N>) -> Vec<uint> {
         // Add several nodes at once.
-        let node_refs = Vec::<&Node>::new();
+        // Returns the index of each given node, in order. Nodes already
+        // in the graph aren't added again, but their index is still returned.
+        let mut node_indices = Vec::<uint>::new();
         for node in nodes.into_iter() {
-            node_refs.push(self.add_node(node));
+            node_indices.push(self.add_node(node));
         }
-        return node_refs;
+        return node_indices;
     }
 
-    pub fn set_node_attr(&mut self, node: &Node,
-                     node_attr: HashMap<String, String>) {
+    pub fn set_node_attr(&mut self, node: &N,
+                         node_attr: HashMap<String, String>)
+                         -> Result<(), GraphError> {
+        // Replace the whole attribute map of a node
         if !self.has_node(node) {
-            panic!("Node does not exist in graph.");
+            return Err(GraphError::NodeNotFound);
         }
         let index = self.get_index(node);
         self.attr_list.insert(index, node_attr);
+        return Ok(());
+    }
+
+    pub fn get_node_attr(&self, node: &N)
+                         -> Result<&HashMap<String, String>, GraphError> {
+        // Return the whole attribute map of a node
+        if !self.has_node(node) {
+            return Err(GraphError::NodeNotFound);
+        }
+        return Ok(&self.attr_list[self.get_index(node)]);
+    }
+
+    pub fn get_node_attr_value(&self, node: &N, key: &str)
+                               -> Option<&String> {
+        // Return a single attribute of a node.
+        // None if either the node or the key doesn't exist.
+        if !self.has_node(node) {
+            return None;
+        }
+        return self.attr_list[self.get_index(node)].get(&key.to_string());
+    }
+
+    pub fn set_node_attr_value(&mut self, node: &N, key: String,
+                               value: String) -> Result<(), GraphError> {
+        // Insert or update one attribute, leaving the others in place
+        if !self.has_node(node) {
+            return Err(GraphError::NodeNotFound);
+        }
+        let index = self.get_index(node);
+        self.attr_list[index].insert(key, value);
+        return Ok(());
+    }
+
+    pub fn remove_node_attr_value(&mut self, node: &N, key: &str)
+                                  -> Option<String> {
+        // Remove one attribute and return its value, if it was set
+        if !self.has_node(node) {
+            return None;
+        }
+        let index = self.get_index(node);
+        return self.attr_list[index].remove(&key.to_string());
     }
 
-    pub fn remove_node(&mut self, node: &Node) -> Result<Node, GraphError> {
+    pub fn remove_node(&mut self, node: &N) -> Result<N, GraphError> {
         // Check for existence and remove the given node.
         // All edges connected to this node are removed, too
+        if !self.has_node(node) {
+            return Err(GraphError::NodeNotFound);
+        }
+        let index = self.get_index(node);
+        return self.remove_node_at(index);
+    }
+
+    pub fn remove_node_at(&mut self, rm_node_index: uint)
+                          -> Result<N, GraphError> {
+        // Remove the node at the given index, along with its edges.
+        // NodeNotFound if the index is out of range.
 
         // Manually manage the raw ptr to the removed node
         // We do these three things:
@@ -85,192 +257,4781 @@ impl Graph {
         // We're using raw pointers so we need to be careful as Rust
         // won't save us if we mess up.
 
-        if !self.has_node(node) {
+        if rm_node_index >= self.nodes.len() {
             return Err(GraphError::NodeNotFound);
         }
 
-        let rm_node_index = self.get_index(node);
-        let mut index: uint;
         // clone so that double borrow doesn't occur
-        let mut conn_nodes = self.adj_list[rm_node_index].clone();
-        // type(conn_node) == &Vec<uint>
-
-        for conn_node in conn_nodes.iter() {
-            // type(conn_node) == &uint
-            let nodes_vec = &mut self.adj_list[*conn_node];
-            // Get index of the node to be removed
-            index = 0;
-            for node_ref_index in nodes_vec.iter() {
-                if *node_ref_index == rm_node_index {
-                    break;
-                }
-                index += 1;
+        let conn_nodes = self.adj_list[rm_node_index].clone();
+        // type(conn_nodes) == Vec<(uint, f64)>
+
+        for &(conn_node, _) in conn_nodes.iter() {
+            self.edge_attr_list.remove(&edge_key(rm_node_index, conn_node));
+            // The removed node's own Vec (self-loop) goes away entirely
+            if conn_node != rm_node_index {
+                self.adj_list[conn_node].retain(|&(n, _)| n != rm_node_index);
             }
-            nodes_vec.swap_remove(index);
         }
-        // Remove the key to node in adj_list
+        // Remove the key to node in adj_list, and its attributes
         self.adj_list.remove(&rm_node_index);
+        self.attr_list.remove(&rm_node_index);
 
         // Now remove the actual node
-        let ret_node: Node;
+        let ret_node: N;
         match self.nodes.swap_remove(rm_node_index) {
             Some(x) => { ret_node = x; }
             None => { panic!("Shouldn't reach here!"); }
         };
 
-        // Change all of last node's index to rm_node_index
-        // (See def of swap_remove)
-        let last_node_index = self.nodes.len() + 1; // since one node was removed
+        // Keep node_index in sync: drop the removed node, and point the
+        // node that swap_remove moved into its slot (if any) at it.
+        self.node_index.remove(&ret_node);
+        if rm_node_index < self.nodes.len() {
+            let moved_node = self.nodes[rm_node_index].clone();
+            self.node_index.insert(moved_node, rm_node_index);
+        }
+
+        // swap_remove moved the last node into rm_node_index. Its old index
+        // is the new length of `nodes`. Nothing moved if we removed the last.
+        let last_node_index = self.nodes.len();
+        if rm_node_index == last_node_index {
+            return Ok(ret_node);
+        }
         // Wherever `last_node_index` occurs, replace it with `rm_node_index`
 
-        conn_nodes = self.adj_list[last_node_index].clone();
+        let mut moved_nbrs = match self.adj_list.remove(&last_node_index) {
+            Some(nbrs) => nbrs,
+            None => { panic!("Shouldn't reach here!"); }
+        };
 
-        for conn_node in conn_nodes.iter() {
-            let nodes_vec = &mut self.adj_list[*conn_node];
-            // Get index of the node to be corrected
-            index = 0;
-            for node_ref in nodes_vec.iter() {
-                if *node_ref == last_node_index {
-                    break;
+        for &(conn_node, _) in moved_nbrs.iter() {
+            // The moved node's edge attributes need re-keying to match. A
+            // self-loop is in the Vec twice, but only re-keyed the first time.
+            let other = if conn_node == last_node_index {
+                rm_node_index
+            } else {
+                conn_node
+            };
+            match self.edge_attr_list.remove(&edge_key(last_node_index,
+                                                       conn_node)) {
+                Some(attrs) => {
+                    self.edge_attr_list.insert(edge_key(rm_node_index, other),
+                                               attrs);
+                }
+                None => {}
+            };
+
+            if conn_node == last_node_index {
+                continue;
+            }
+            // Point the neighbor's entry back at the node's new index,
+            // keeping the weight
+            for entry in self.adj_list[conn_node].iter_mut() {
+                let (nbr, weight) = *entry;
+                if nbr == last_node_index {
+                    *entry = (rm_node_index, weight);
                 }
-                index += 1;
             }
-            nodes_vec[index] = rm_node_index;
         }
 
-        self.adj_list.remove(&last_node_index);
-        self.adj_list.insert(rm_node_index, conn_nodes);
+        // ...and the same for the moved node's own self-loop entries
+        for entry in moved_nbrs.iter_mut() {
+            let (nbr, weight) = *entry;
+            if nbr == last_node_index {
+                *entry = (rm_node_index, weight);
+            }
+        }
+        self.adj_list.insert(rm_node_index, moved_nbrs);
 
-        // Remove the node from attr_list
-        self.attr_list.remove(&rm_node_index);
+        // The moved node's attributes follow it to its new index
+        match self.attr_list.remove(&last_node_index) {
+            Some(attrs) => { self.attr_list.insert(rm_node_index, attrs); }
+            None => {}
+        };
         // ...and, all done! Now, we return the removed node.
         return Ok(ret_node);
     }
 
-    pub fn add_edge(&mut self, node1: &Node, node2: &Node) {
-        // Add a single edge between two nodes
-        // Nodes may or may not be already added.
-
-        // Check if edge is already present
+    pub fn add_edge(&mut self, node1: &N, node2: &N) {
+        // Add a single edge between two nodes, with the default weight 1.0
+        // Nodes may or may not be already added. An edge that's already
+        // there is left alone, weight and all.
         if self.has_edge(node1, node2) {
             return;
         }
+        self.add_weighted_edge(node1, node2, 1.0);
+    }
 
-        // Check if nodes exist already
+    pub fn add_weighted_edge(&mut self, node1: &N, node2: &N,
+                             weight: f64) {
+        // Add a single edge of the given weight between two nodes
+        // Nodes may or may not be already added. If the edge is already
+        // there, its weight is replaced.
+
+        // Add any missing node by value first. The parameters stay as they
+        // are; the indices come from node_index once both nodes exist.
+        // This has to happen before the has_edge check below.
         if !self.has_node(node1) {
-            let clone_node1 = node1.clone();
-            node1 = self.add_node(clone_node1);
+            self.add_node(node1.clone());
         }
 
         if !self.has_node(node2) {
-            let clone_node2 = node2.clone();
-            node2 = self.add_node(clone_node2);
+            self.add_node(node2.clone());
         }
         let node1_index = self.get_index(node1);
         let node2_index = self.get_index(node2);
 
+        // Self-loops are silently skipped unless they've been allowed.
+        // The node itself is still added.
+        if node1_index == node2_index && !self.allow_self_loops {
+            return;
+        }
+
+        // If the edge is already present, just update its weight
+        if self.has_edge(node1, node2) {
+            self.set_weight(node1_index, node2_index, weight);
+            self.set_weight(node2_index, node1_index, weight);
+            return;
+        }
+
         // Add edges
         // Now we add the edge twice - 1-2 and 2-1
-        self.adj_list[node1_index].push(node2_index);
-        self.adj_list[node2_index].push(node1_index);
+        self.adj_list[node1_index].push((node2_index, weight));
+        self.adj_list[node2_index].push((node1_index, weight));
+        self.edge_attr_list.insert(edge_key(node1_index, node2_index),
+                                   HashMap::new());
     }
 
-    // Helpers from here on out
-    // To be used internally only. No public API.
+    pub fn add_edges_multiple(&mut self, edges: Vec<(N, N)>) {
+        // Add several edges at once, with the default weight 1.0.
+        // Missing nodes are added. As with add_edge, an edge that is already
+        // in the graph, or that comes up twice in the batch, is only added
+        // once and keeps the weight it has.
+        for (node1, node2) in edges.into_iter() {
+            self.add_edge(&node1, &node2);
+        }
+    }
 
-    fn edges(&self) -> Vec<Edge> {
-        // Return all edges of a Graph
-        let mut edge_vec = Vec::<Edge>::new();
-        let mut visited = Vec::<uint>::new();
-        for (node, nbrs) in self.adj_list.iter() {
-            for nbr in nbrs.iter() { // methods work on refs, too
-                // nbr of type &uint
-                if !visited.contains(nbr) { // uint is copyable
-                    edge_vec.push(Edge(*node, *nbr));
+    pub fn allow_self_loops(&mut self, allow: bool) {
+        // Choose whether add_edge may add self-loops (default false).
+        // Loops that are already in the graph stay when this is turned off.
+        self.allow_self_loops = allow;
+    }
+
+    pub fn self_loops(&self) -> Vec<&N> {
+        // Nodes that have an edge to themselves, in node order
+        let mut looped = Vec::<&N>::new();
+        for index in range(0, self.nodes.len()) {
+            for &(nbr, _) in self.adj_list[index].iter() {
+                if nbr == index {
+                    looped.push(&self.nodes[index]);
+                    break;
                 }
             }
-            visited.push(*node);
         }
-        return edge_vec;
+        return looped;
+    }
+
+    pub fn has_self_loop(&self, node: &N) -> Result<bool, GraphError> {
+        // Check whether a node has an edge to itself
+        if !self.has_node(node) {
+            return Err(GraphError::NodeNotFound);
+        }
+        let index = self.get_index(node);
+        return Ok(self.adj_list[index].iter().any(|&(nbr, _)| nbr == index));
     }
 
+    pub fn set_edge_attr(&mut self, node1: &N, node2: &N,
+                         edge_attr: HashMap<String, String>)
+                         -> Result<(), GraphError> {
+        // Replace the attribute map of the edge between two nodes
+        let key = try!(self.existing_edge_key(node1, node2));
+        self.edge_attr_list.insert(key, edge_attr);
+        return Ok(());
+    }
 
-    fn get_index(&self, node: &Node) -> uint {
-        // All nodes are unique which allows us to assign each node an index
-        // Run through the Vec to get the index
-        let mut index = 0;
-        for node_ref in self.nodes.iter() {
-            if *node_ref == *node {
-                return index;
+    pub fn get_edge_attr(&self, node1: &N, node2: &N)
+                         -> Result<&HashMap<String, String>, GraphError> {
+        // Return the attribute map of the edge between two nodes
+        let key = try!(self.existing_edge_key(node1, node2));
+        return Ok(&self.edge_attr_list[key]);
+    }
+
+    pub fn edge_weight(&self, node1: &N, node2: &N)
+                       -> Result<f64, GraphError> {
+        // Return the weight of the edge between two nodes
+        if !self.has_node(node1) || !self.has_node(node2) {
+            return Err(GraphError::NodeNotFound);
+        }
+
+        let node2_index = self.get_index(node2);
+        for &(nbr, weight) in self.adj_list[self.get_index(node1)].iter() {
+            if nbr == node2_index {
+                return Ok(weight);
             }
-            index += 1;
         }
-        // No node found. Node doesn't exist
-        // Since it is internal function, there should occur no such situation
-        // Panic.
-        panic!("Node does not exist.");
+        return Err(GraphError::EdgeNotFound);
     }
 
+    pub fn remove_edge(&mut self, node1: &N, node2: &N)
+                       -> Result<(), GraphError> {
+        // Remove the single edge between two nodes.
+        // Both nodes, and their attributes, stay in the graph.
+        if !self.has_node(node1) || !self.has_node(node2) {
+            return Err(GraphError::NodeNotFound);
+        }
 
-    fn has_node(&self, node: &Node) -> bool {
-        for n in self.nodes.iter() {
-            if *n == *node {
-                return true;
+        if !self.has_edge(node1, node2) {
+            return Err(GraphError::EdgeNotFound);
+        }
+
+        let node1_index = self.get_index(node1);
+        let node2_index = self.get_index(node2);
+
+        // The edge was added twice - 1-2 and 2-1 - so remove both entries.
+        // Vec::remove keeps the remaining neighbors in insertion order.
+        let pos1 = self.adj_list[node1_index].iter()
+                       .position(|&(n, _)| n == node2_index).unwrap();
+        self.adj_list[node1_index].remove(pos1);
+
+        let pos2 = self.adj_list[node2_index].iter()
+                       .position(|&(n, _)| n == node1_index).unwrap();
+        self.adj_list[node2_index].remove(pos2);
+
+        // The edge's attributes go with it
+        self.edge_attr_list.remove(&edge_key(node1_index, node2_index));
+        return Ok(());
+    }
+
+    pub fn contract_edge(&mut self, node1: &N, node2: &N)
+                         -> Result<(), GraphError> {
+        // Contract the edge between two nodes, merging node2 into node1.
+        // node2's neighbors become node1's, edges node1 already has are
+        // kept as they are, and the edge between the two is dropped rather
+        // than turned into a self-loop. Where both nodes set the same
+        // attribute, node1's value is kept. node2 is then removed.
+        if !self.has_node(node1) || !self.has_node(node2) {
+            return Err(GraphError::NodeNotFound);
+        }
+        if !self.has_edge(node1, node2) {
+            return Err(GraphError::EdgeNotFound);
+        }
+
+        let index1 = self.get_index(node1);
+        let index2 = self.get_index(node2);
+        // Contracting a self-loop just leaves its node behind
+        if index1 == index2 {
+            return self.remove_edge(node1, node2);
+        }
+
+        let moved_attrs = self.attr_list[index2].clone();
+        for (key, value) in moved_attrs.into_iter() {
+            if !self.attr_list[index1].contains_key(&key) {
+                self.attr_list[index1].insert(key, value);
             }
         }
-        return false;
+
+        // clone so that double borrow doesn't occur
+        let nbrs = self.adj_list[index2].clone();
+        for &(nbr, weight) in nbrs.iter() {
+            if nbr == index1 || nbr == index2 {
+                continue;
+            }
+            let nbr_node = self.nodes[nbr].clone();
+            if !self.has_edge(node1, &nbr_node) {
+                self.add_weighted_edge(node1, &nbr_node, weight);
+                let attrs = self.edge_attr_list[edge_key(index2, nbr)].clone();
+                self.edge_attr_list.insert(edge_key(index1, nbr), attrs);
+            }
+        }
+
+        try!(self.remove_node(node2));
+        return Ok(());
     }
 
-    fn has_edge(&self, node1: &Node, node2: &Node) -> bool {
-        let n1_ind = self.get_index(node1);
-        let n2_ind = self.get_index(node2);
-        if self.adj_list[n1_ind].contains(&n2_ind) {
-            return true;
+    pub fn has_multi_edges(&self) -> bool {
+        // Check the adj_list for a pair of nodes joined more than once.
+        // add_edge never does this, so it points at a bug elsewhere.
+        // A self-loop is normally in its node's Vec twice, so only a third
+        // entry counts there.
+        for index in range(0, self.nodes.len()) {
+            let mut seen = HashMap::<uint, uint>::new();
+            for &(nbr, _) in self.adj_list[index].iter() {
+                let count = match seen.get(&nbr) {
+                    Some(&count) => count + 1,
+                    None => 1,
+                };
+                let limit = if nbr == index { 2 } else { 1 };
+                if count > limit {
+                    return true;
+                }
+                seen.insert(nbr, count);
+            }
         }
         return false;
     }
 
-    fn extract_node(&self, node: Node) -> String {
-        let node_name = match node {
-            Node::Str(s) => s,
-            Node::Int(v) => v.to_string()
-        };
-        return node_name;
+    pub fn deduplicate_edges(&mut self) {
+        // Collapse repeated adj_list entries into one edge, keeping the
+        // first entry (and its weight) for every neighbor.
+        for index in range(0, self.nodes.len()) {
+            let mut seen = HashMap::<uint, uint>::new();
+            let mut nbrs = Vec::<(uint, f64)>::new();
+            for &(nbr, weight) in self.adj_list[index].iter() {
+                let count = match seen.get(&nbr) {
+                    Some(&count) => count + 1,
+                    None => 1,
+                };
+                let limit = if nbr == index { 2 } else { 1 };
+                if count <= limit {
+                    nbrs.push((nbr, weight));
+                }
+                seen.insert(nbr, count);
+            }
+            self.adj_list.insert(index, nbrs);
+        }
     }
 
-    fn existing_node(&self, node: &Node) -> &Node {
-        // Return ref to existing node
-        for n in self.nodes.iter() {
-            if *node == *n {
-                return n;
+    pub fn clear_edges(&mut self) {
+        // Remove every edge, keeping all nodes and their attributes
+        for nbrs in self.adj_list.values_mut() {
+            nbrs.clear();
+        }
+        self.edge_attr_list.clear();
+    }
+
+    pub fn clear(&mut self) {
+        // Remove all nodes and edges so the graph can be reused.
+        // The name and the self-loop setting are kept, and the next node
+        // added gets index 0 again.
+        self.nodes.clear();
+        self.attr_list.clear();
+        self.adj_list.clear();
+        self.node_index.clear();
+        self.edge_attr_list.clear();
+    }
+
+    pub fn neighbors(&self, node: &N) -> Result<Vec<&N>, GraphError> {
+        // Return the neighbors of a node, in the order the edges were added
+        if !self.has_node(node) {
+            return Err(GraphError::NodeNotFound);
+        }
+
+        let mut nbrs = Vec::<&N>::new();
+        for &(nbr_index, _) in self.adj_list[self.get_index(node)].iter() {
+            nbrs.push(&self.nodes[nbr_index]);
+        }
+        return Ok(nbrs);
+    }
+
+    pub fn relabel_nodes<F: Fn(&N) -> N>(&mut self, f: F)
+                                         -> Result<(), GraphError> {
+        // Replace every node with f(node). Indices, edges and attributes
+        // all stay where they are. If two nodes would get the same new
+        // label, fails with LabelCollision and leaves the graph unchanged.
+        let mut new_nodes = Vec::<N>::new();
+        let mut new_index = HashMap::<N, uint>::new();
+        for (index, node) in self.nodes.iter().enumerate() {
+            let label = f(node);
+            if new_index.contains_key(&label) {
+                return Err(GraphError::LabelCollision);
             }
+            new_index.insert(label.clone(), index);
+            new_nodes.push(label);
         }
-        panic!("No such node.");
+        self.nodes = new_nodes;
+        self.node_index = new_index;
+        return Ok(());
     }
 
-    fn last_node(&self) -> &Node { &self.nodes[self.nodes.len()] }
-}
+    pub fn adjacency(&self) -> HashMap<&N, Vec<&N>> {
+        // Snapshot of the adjacency list, mapping every node to its
+        // neighbors (in the order given by `neighbors`)
+        let mut adjacency = HashMap::<&N, Vec<&N>>::new();
+        for (index, node) in self.nodes.iter().enumerate() {
+            let mut nbrs = Vec::<&N>::new();
+            for &(nbr, _) in self.adj_list[index].iter() {
+                nbrs.push(&self.nodes[nbr]);
+            }
+            adjacency.insert(node, nbrs);
+        }
+        return adjacency;
+    }
 
+    pub fn node_at(&self, index: uint) -> Option<&N> {
+        // Node at the given index, for turning index-keyed results (like
+        // bipartite_coloring) back into nodes. None if out of range.
+        if index >= self.nodes.len() {
+            return None;
+        }
+        return Some(&self.nodes[index]);
+    }
 
-impl Show for Graph {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // Shows textual repr of Graph
-        try!(write!(f, "{{ Nodes: "));
-        for n in self.nodes.iter() {
-            try!(write!(f, "{}, ", n));
+    pub fn contains_node(&self, node: &N) -> bool {
+        // Check whether a node is in the graph
+        return self.has_node(node);
+    }
+
+    pub fn contains_edge(&self, node1: &N, node2: &N) -> bool {
+        // Check whether two nodes are adjacent.
+        // False, rather than a panic, if either node isn't in the graph.
+        return self.has_edge(node1, node2);
+    }
+
+    pub fn degree(&self, node: &N) -> Result<uint, GraphError> {
+        // Return the number of edges incident on a node.
+        // A self-loop adds one for every entry it has in the adj_list.
+        if !self.has_node(node) {
+            return Err(GraphError::NodeNotFound);
         }
-        try!(writeln!(f, ""));
-        try!(write!(f, "Edges: "));
-        for edge in self.edges().iter() {
-            try!(write!(f, "{}, ", edge));
+        return Ok(self.adj_list[self.get_index(node)].len());
+    }
+
+    pub fn clustering_coefficient(&self, node: &N)
+                                  -> Result<f64, GraphError> {
+        // Fraction of the pairs of a node's neighbors that are adjacent
+        // themselves. Nodes with fewer than two neighbors get 0.0.
+        // Self-loops don't make a node its own neighbor here.
+        if !self.has_node(node) {
+            return Err(GraphError::NodeNotFound);
         }
-        write!(f, "}}")
+        let index = self.get_index(node);
+        return Ok(self.local_clustering(index));
     }
-}
 
-impl Show for Edge {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let Edge(node1, node2) = *self;
-        write!(f, "{}--{}", node1, node2)
+    pub fn average_clustering(&self) -> f64 {
+        // Mean clustering coefficient over all nodes, 0.0 for an empty graph
+        if self.nodes.is_empty() {
+            return 0.0;
+        }
+        let mut total = 0.0;
+        for index in range(0, self.nodes.len()) {
+            total += self.local_clustering(index);
+        }
+        return total / self.nodes.len() as f64;
+    }
+
+    pub fn count_triangles(&self) -> uint {
+        // Number of distinct triangles (3-cliques).
+        // For each edge u-v with u < v, count the common neighbors w > v,
+        // so every triangle is only counted from its lowest edge.
+        let mut nbr_sets = Vec::<HashSet<uint>>::new();
+        for index in range(0, self.nodes.len()) {
+            let mut nbrs = HashSet::<uint>::new();
+            for &(nbr, _) in self.adj_list[index].iter() {
+                nbrs.insert(nbr);
+            }
+            nbr_sets.push(nbrs);
+        }
+
+        let mut triangles = 0u;
+        for &(node1, node2, _) in self.weighted_edges().iter() {
+            if node1 == node2 {
+                continue;
+            }
+            for &common in nbr_sets[node1].intersection(&nbr_sets[node2]) {
+                if common > node2 {
+                    triangles += 1;
+                }
+            }
+        }
+        return triangles;
+    }
+
+    pub fn maximal_cliques(&self) -> Vec<Vec<&N>> {
+        // Every clique that can't be grown by adding another node, found
+        // by Bron-Kerbosch with pivoting. Worst case exponential.
+        // Each clique is in node order, and self-loops are ignored.
+        let n = self.nodes.len();
+        if n == 0 {
+            return Vec::new();
+        }
+        let mut nbr_sets = Vec::<HashSet<uint>>::new();
+        for index in range(0, n) {
+            let mut nbrs = HashSet::<uint>::new();
+            for &(nbr, _) in self.adj_list[index].iter() {
+                if nbr != index {
+                    nbrs.insert(nbr);
+                }
+            }
+            nbr_sets.push(nbrs);
+        }
+
+        let mut found = Vec::<Vec<uint>>::new();
+        bron_kerbosch(nbr_sets.as_slice(), &mut Vec::new(),
+                      range(0, n).collect(), Vec::new(), &mut found);
+
+        let mut cliques = Vec::<Vec<&N>>::new();
+        for clique in found.iter_mut() {
+            clique.sort();
+            cliques.push(clique.iter().map(|&i| &self.nodes[i]).collect());
+        }
+        return cliques;
+    }
+
+    pub fn degree_sequence(&self) -> Vec<uint> {
+        // Degrees of all the nodes, highest first
+        let mut degrees = Vec::<uint>::new();
+        for index in range(0, self.nodes.len()) {
+            degrees.push(self.adj_list[index].len());
+        }
+        degrees.sort_by(|a, b| b.cmp(a));
+        return degrees;
+    }
+
+    pub fn nodes_by_degree(&self) -> Vec<(&N, uint)> {
+        // Nodes with their degrees, highest degree first.
+        // Nodes of equal degree stay in insertion order.
+        let mut by_degree = Vec::<(&N, uint)>::new();
+        for index in self.indices_by_degree().iter() {
+            by_degree.push((&self.nodes[*index], self.adj_list[*index].len()));
+        }
+        return by_degree;
+    }
+
+    pub fn degree_histogram(&self) -> HashMap<uint, uint> {
+        // Map each degree that occurs to the number of nodes having it
+        let mut histogram = HashMap::<uint, uint>::new();
+        for index in range(0, self.nodes.len()) {
+            let degree = self.adj_list[index].len();
+            let count = match histogram.get(&degree) {
+                Some(&count) => count,
+                None => 0,
+            };
+            histogram.insert(degree, count + 1);
+        }
+        return histogram;
+    }
+
+    pub fn bfs(&self, start: &N) -> Result<Vec<&N>, GraphError> {
+        // Breadth-first traversal starting at `start`.
+        // Nodes are returned in the order they are first visited; nodes
+        // that can't be reached from `start` don't show up at all.
+        if !self.has_node(start) {
+            return Err(GraphError::NodeNotFound);
+        }
+
+        let mut order = Vec::<&N>::new();
+        for index in self.bfs_indices(self.get_index(start)).iter() {
+            order.push(&self.nodes[*index]);
+        }
+        return Ok(order);
+    }
+
+    pub fn connected_components(&self) -> Vec<Vec<&N>> {
+        // Split the graph into its connected components.
+        // Components come out in the order of their first inserted node.
+        let mut components = Vec::<Vec<&N>>::new();
+        let mut visited = HashSet::<uint>::new();
+
+        for index in range(0, self.nodes.len()) {
+            if visited.contains(&index) {
+                continue;
+            }
+            let mut component = Vec::<&N>::new();
+            for member in self.bfs_indices(index).iter() {
+                visited.insert(*member);
+                component.push(&self.nodes[*member]);
+            }
+            components.push(component);
+        }
+        return components;
+    }
+
+    pub fn largest_component(&self) -> Option<Graph<N>> {
+        // Induced subgraph of the connected component with the most nodes.
+        // Ties go to the component found first, as in connected_components.
+        // None for the empty graph.
+        let mut largest = Vec::<uint>::new();
+        let mut visited = HashSet::<uint>::new();
+        for index in range(0, self.nodes.len()) {
+            if visited.contains(&index) {
+                continue;
+            }
+            let component = self.bfs_indices(index);
+            for member in component.iter() {
+                visited.insert(*member);
+            }
+            if component.len() > largest.len() {
+                largest = component;
+            }
+        }
+
+        if largest.is_empty() {
+            return None;
+        }
+        // Keep the nodes in their original order
+        largest.sort();
+        return Some(self.induced_subgraph(largest.as_slice()));
+    }
+
+    pub fn is_connected(&self) -> bool {
+        // Check whether every node can be reached from every other one.
+        // The empty graph has no unreachable nodes, so it counts as connected.
+        if self.nodes.is_empty() {
+            return true;
+        }
+        return self.bfs_indices(0).len() == self.nodes.len();
+    }
+
+    pub fn is_tree(&self) -> bool {
+        // Check for a connected graph with exactly V - 1 edges, which can't
+        // have a cycle (self-loops or repeated edges included).
+        // A single node is a tree; the empty graph isn't.
+        if self.nodes.is_empty() {
+            return false;
+        }
+        return self.edge_count() == self.nodes.len() - 1 && self.is_connected();
+    }
+
+    pub fn dfs(&self, start: &N) -> Result<Vec<&N>, GraphError> {
+        // Depth-first traversal starting at `start`, in preorder.
+        // Uses an explicit stack instead of recursion so that deep graphs
+        // can't overflow the call stack.
+        if !self.has_node(start) {
+            return Err(GraphError::NodeNotFound);
+        }
+
+        let mut order = Vec::<&N>::new();
+        let mut visited = HashSet::<uint>::new();
+        let mut stack = vec![self.get_index(start)];
+
+        loop {
+            let current = match stack.pop() {
+                Some(x) => x,
+                None => break,
+            };
+            if visited.contains(&current) {
+                continue;
+            }
+            visited.insert(current);
+            order.push(&self.nodes[current]);
+
+            // Push in reverse so the first neighbor in the adj_list
+            // is the first one expanded.
+            for &(nbr, _) in self.adj_list[current].iter().rev() {
+                if !visited.contains(&nbr) {
+                    stack.push(nbr);
+                }
+            }
+        }
+        return Ok(order);
+    }
+
+    pub fn shortest_path(&self, start: &N, end: &N)
+                         -> Result<Option<Vec<&N>>, GraphError> {
+        // Path with the fewest hops between two nodes, found by BFS.
+        // The path includes both endpoints. Returns Ok(None) when `end`
+        // can't be reached from `start`.
+        if !self.has_node(start) || !self.has_node(end) {
+            return Err(GraphError::NodeNotFound);
+        }
+
+        let start_index = self.get_index(start);
+        let end_index = self.get_index(end);
+
+        // parent[n] is the node n was first reached from
+        let mut parent = HashMap::<uint, uint>::new();
+        let mut visited = HashSet::<uint>::new();
+        let mut queue = RingBuf::<uint>::new();
+
+        visited.insert(start_index);
+        queue.push_back(start_index);
+
+        loop {
+            let current = match queue.pop_front() {
+                Some(x) => x,
+                None => break,
+            };
+            if current == end_index {
+                break;
+            }
+
+            for &(nbr, _) in self.adj_list[current].iter() {
+                if !visited.contains(&nbr) {
+                    visited.insert(nbr);
+                    parent.insert(nbr, current);
+                    queue.push_back(nbr);
+                }
+            }
+        }
+
+        if !visited.contains(&end_index) {
+            return Ok(None);
+        }
+
+        return Ok(Some(self.build_path(&parent, start_index, end_index)));
+    }
+
+    pub fn is_reachable(&self, start: &N, target: &N)
+                        -> Result<bool, GraphError> {
+        // Check whether there is a path from `start` to `target`.
+        // Same BFS as shortest_path, but it stops as soon as `target` turns
+        // up and doesn't keep track of the path.
+        if !self.has_node(start) || !self.has_node(target) {
+            return Err(GraphError::NodeNotFound);
+        }
+
+        let start_index = self.get_index(start);
+        let target_index = self.get_index(target);
+        if start_index == target_index {
+            return Ok(true);
+        }
+
+        let mut visited = HashSet::<uint>::new();
+        let mut queue = RingBuf::<uint>::new();
+        visited.insert(start_index);
+        queue.push_back(start_index);
+
+        loop {
+            let current = match queue.pop_front() {
+                Some(x) => x,
+                None => break,
+            };
+            for &(nbr, _) in self.adj_list[current].iter() {
+                if nbr == target_index {
+                    return Ok(true);
+                }
+                if !visited.contains(&nbr) {
+                    visited.insert(nbr);
+                    queue.push_back(nbr);
+                }
+            }
+        }
+        return Ok(false);
+    }
+
+    pub fn all_simple_paths(&self, start: &N, end: &N, max_len: Option<uint>)
+                            -> Result<Vec<Vec<&N>>, GraphError> {
+        // Every path from `start` to `end` that doesn't repeat a node, with
+        // at most `max_len` edges if a cap is given. Found by backtracking
+        // DFS, in adj_list order. The path from a node to itself is just
+        // that node.
+        if !self.has_node(start) || !self.has_node(end) {
+            return Err(GraphError::NodeNotFound);
+        }
+
+        let start_index = self.get_index(start);
+        let end_index = self.get_index(end);
+        if start_index == end_index {
+            return Ok(vec![vec![&self.nodes[start_index]]]);
+        }
+
+        let mut paths = Vec::<Vec<&N>>::new();
+        // The path so far, and for each node on it the position in its
+        // adj_list of the next neighbor to try
+        let mut path = vec![start_index];
+        let mut next = vec![0u];
+        let mut on_path = HashSet::<uint>::new();
+        on_path.insert(start_index);
+
+        loop {
+            let depth = path.len();
+            if depth == 0 {
+                break;
+            }
+            let current = path[depth - 1];
+            let pos = next[depth - 1];
+
+            // Tried every neighbor, so back up a step
+            if pos >= self.adj_list[current].len() {
+                path.pop();
+                next.pop();
+                on_path.remove(&current);
+                continue;
+            }
+            next[depth - 1] += 1;
+
+            let (nbr, _) = self.adj_list[current][pos];
+            if on_path.contains(&nbr) {
+                continue;
+            }
+            // Going on to nbr would give a path of `depth` edges
+            match max_len {
+                Some(max) if depth > max => { continue; }
+                _ => {}
+            };
+
+            if nbr == end_index {
+                let mut found = Vec::<&N>::new();
+                for index in path.iter() {
+                    found.push(&self.nodes[*index]);
+                }
+                found.push(&self.nodes[end_index]);
+                paths.push(found);
+                continue;
+            }
+            path.push(nbr);
+            next.push(0);
+            on_path.insert(nbr);
+        }
+        return Ok(paths);
+    }
+
+    pub fn distances_from(&self, start: &N)
+                          -> Result<HashMap<uint, uint>, GraphError> {
+        // Number of hops from `start` to every node it can reach, keyed by
+        // node index. Unreachable nodes are left out of the map.
+        if !self.has_node(start) {
+            return Err(GraphError::NodeNotFound);
+        }
+        return Ok(self.bfs_distances(self.get_index(start)));
+    }
+
+    pub fn eccentricity(&self, node: &N)
+                        -> Result<Option<uint>, GraphError> {
+        // Greatest hop distance from `node` to any other node.
+        // Ok(None) if some node can't be reached from it at all.
+        if !self.has_node(node) {
+            return Err(GraphError::NodeNotFound);
+        }
+        let dist = self.bfs_distances(self.get_index(node));
+        if dist.len() < self.nodes.len() {
+            return Ok(None);
+        }
+        return Ok(dist.values().max().map(|&d| d));
+    }
+
+    pub fn diameter(&self) -> Option<uint> {
+        // Largest hop distance between any two nodes (max eccentricity).
+        // None if the graph is disconnected, since some distances are then
+        // infinite, and also for the empty graph.
+        return self.eccentricities().and_then(|ecc| {
+            ecc.iter().max().map(|&e| e)
+        });
+    }
+
+    pub fn radius(&self) -> Option<uint> {
+        // Smallest eccentricity of any node.
+        // None for disconnected and empty graphs, as with diameter.
+        return self.eccentricities().and_then(|ecc| {
+            ecc.iter().min().map(|&e| e)
+        });
+    }
+
+    pub fn center(&self) -> Vec<&N> {
+        // Nodes whose eccentricity equals the radius, in node order.
+        // Empty for disconnected and empty graphs, which have no radius.
+        let ecc = match self.eccentricities() {
+            Some(x) => x,
+            None => { return Vec::new(); }
+        };
+        let radius = *ecc.iter().min().unwrap();
+        let mut center = Vec::<&N>::new();
+        for (index, &e) in ecc.iter().enumerate() {
+            if e == radius {
+                center.push(&self.nodes[index]);
+            }
+        }
+        return center;
+    }
+
+    pub fn dijkstra(&self, start: &N, end: &N)
+                    -> Result<Option<(Vec<&N>, f64)>, GraphError> {
+        // Minimum weight path between two nodes, along with its total cost.
+        // Returns Ok(None) when `end` can't be reached from `start`.
+        if !self.has_node(start) || !self.has_node(end) {
+            return Err(GraphError::NodeNotFound);
+        }
+
+        // Dijkstra's algorithm is only correct for non-negative weights
+        for nbrs in self.adj_list.values() {
+            for &(_, weight) in nbrs.iter() {
+                if weight < 0.0 {
+                    return Err(GraphError::NegativeWeight);
+                }
+            }
+        }
+
+        let start_index = self.get_index(start);
+        let end_index = self.get_index(end);
+
+        // dist[n] is the cheapest known cost of getting to n
+        let mut dist = HashMap::<uint, f64>::new();
+        let mut parent = HashMap::<uint, uint>::new();
+        let mut heap = BinaryHeap::<HeapEntry>::new();
+
+        dist.insert(start_index, 0.0);
+        heap.push(HeapEntry { cost: 0.0, index: start_index });
+
+        loop {
+            let HeapEntry { cost, index } = match heap.pop() {
+                Some(x) => x,
+                None => break,
+            };
+            if index == end_index {
+                break;
+            }
+            // Stale entry - a cheaper path to this node was already found
+            if cost > dist[index] {
+                continue;
+            }
+
+            for &(nbr, weight) in self.adj_list[index].iter() {
+                let new_cost = cost + weight;
+                let cheaper = match dist.get(&nbr) {
+                    Some(&old_cost) => new_cost < old_cost,
+                    None => true,
+                };
+                if cheaper {
+                    dist.insert(nbr, new_cost);
+                    parent.insert(nbr, index);
+                    heap.push(HeapEntry { cost: new_cost, index: nbr });
+                }
+            }
+        }
+
+        if !dist.contains_key(&end_index) {
+            return Ok(None);
+        }
+
+        let path = self.build_path(&parent, start_index, end_index);
+        return Ok(Some((path, dist[end_index])));
+    }
+
+    pub fn has_cycle(&self) -> bool {
+        // Check whether the graph has a cycle, i.e. isn't a forest.
+        // DFS through every component, remembering each node's parent.
+        // Reaching an already visited node that isn't our parent means
+        // there is a second path to it. Self-loops count as cycles.
+        let mut visited = HashSet::<uint>::new();
+        let mut parent = HashMap::<uint, uint>::new();
+
+        for root in range(0, self.nodes.len()) {
+            if visited.contains(&root) {
+                continue;
+            }
+            visited.insert(root);
+            let mut stack = vec![root];
+
+            loop {
+                let current = match stack.pop() {
+                    Some(x) => x,
+                    None => break,
+                };
+
+                for &(nbr, _) in self.adj_list[current].iter() {
+                    if nbr == current {
+                        return true;
+                    }
+                    if !visited.contains(&nbr) {
+                        visited.insert(nbr);
+                        parent.insert(nbr, current);
+                        stack.push(nbr);
+                    } else if parent.get(&current) != Some(&nbr) {
+                        return true;
+                    }
+                }
+            }
+        }
+        return false;
+    }
+
+    pub fn girth(&self) -> Option<uint> {
+        // Length of the shortest cycle, None if there isn't one (a forest).
+        // BFS from every node: an edge between two reached nodes, other
+        // than back to the parent, closes a cycle of at most
+        // dist[u] + dist[v] + 1, and from a node on the shortest cycle
+        // that bound is exact. As in has_cycle, a self-loop is a cycle
+        // (of length 1) and repeated edges are not.
+        let n = self.nodes.len();
+        let mut girth = None::<uint>;
+        for root in range(0, n) {
+            let mut dist = Vec::from_elem(n, None::<uint>);
+            let mut parent = Vec::from_elem(n, None::<uint>);
+            dist[root] = Some(0);
+            let mut queue = RingBuf::<uint>::new();
+            queue.push_back(root);
+
+            loop {
+                let current = match queue.pop_front() {
+                    Some(x) => x,
+                    None => break,
+                };
+                let current_dist = dist[current].unwrap();
+
+                for &(nbr, _) in self.adj_list[current].iter() {
+                    if nbr == current {
+                        return Some(1);
+                    }
+                    if parent[current] == Some(nbr) {
+                        continue;
+                    }
+                    match dist[nbr] {
+                        None => {
+                            dist[nbr] = Some(current_dist + 1);
+                            parent[nbr] = Some(current);
+                            queue.push_back(nbr);
+                        }
+                        Some(nbr_dist) if parent[nbr] != Some(current) => {
+                            let length = current_dist + nbr_dist + 1;
+                            if girth.map_or(true, |g| length < g) {
+                                girth = Some(length);
+                            }
+                        }
+                        Some(_) => {}
+                    };
+                }
+            }
+        }
+        return girth;
+    }
+
+    pub fn minimum_spanning_tree(&self) -> Result<Graph<N>, GraphError> {
+        // Minimum spanning tree by Kruskal's algorithm: take the edges
+        // cheapest first, skipping any that would close a cycle.
+        // A disconnected graph gives the minimum spanning forest. All nodes
+        // are kept, along with their attributes and those of the tree edges.
+        // Kruskal copes with any weights, so for now this always succeeds.
+        let mut edges = self.weighted_edges();
+        edges.sort_by(|&(_, _, weight1), &(_, _, weight2)| {
+            match weight1.partial_cmp(&weight2) {
+                Some(ordering) => ordering,
+                None => Ordering::Equal,
+            }
+        });
+
+        let mut graph = Graph::new();
+        for (index, node) in self.nodes.iter().enumerate() {
+            graph.add_node(node.clone());
+            graph.attr_list.insert(index, self.attr_list[index].clone());
+        }
+
+        // Union-find over node indices; each set is one tree of the forest
+        let mut parent = Vec::from_fn(self.nodes.len(), |index| index);
+        for &(node1, node2, weight) in edges.iter() {
+            let root1 = find_root(&mut parent, node1);
+            let root2 = find_root(&mut parent, node2);
+            if root1 == root2 {
+                continue;
+            }
+            parent[root1] = root2;
+
+            graph.add_weighted_edge(&self.nodes[node1], &self.nodes[node2],
+                                    weight);
+            let key = edge_key(node1, node2);
+            graph.edge_attr_list.insert(key, self.edge_attr_list[key].clone());
+        }
+        return Ok(graph);
+    }
+
+    pub fn betweenness_centrality(&self) -> HashMap<uint, f64> {
+        // For every node (keyed by index), the sum over all other pairs of
+        // nodes of the fraction of their shortest paths that go through it.
+        // Brandes' algorithm over hop counts, so weights are ignored.
+        // Scores aren't normalized; each unordered pair counts once.
+        let n = self.nodes.len();
+        let mut centrality = Vec::from_elem(n, 0.0f64);
+
+        for source in range(0, n) {
+            // BFS from source, counting the shortest paths to each node
+            // (sigma) and remembering the nodes they arrive from (preds)
+            let mut order = Vec::<uint>::new();
+            let mut preds = Vec::from_elem(n, Vec::<uint>::new());
+            let mut sigma = Vec::from_elem(n, 0.0f64);
+            let mut dist: Vec<Option<uint>> = Vec::from_elem(n, None);
+            let mut queue = RingBuf::<uint>::new();
+
+            sigma[source] = 1.0;
+            dist[source] = Some(0);
+            queue.push_back(source);
+
+            loop {
+                let current = match queue.pop_front() {
+                    Some(x) => x,
+                    None => break,
+                };
+                order.push(current);
+                let next_dist = dist[current].unwrap() + 1;
+
+                for &(nbr, _) in self.adj_list[current].iter() {
+                    if dist[nbr].is_none() {
+                        dist[nbr] = Some(next_dist);
+                        queue.push_back(nbr);
+                    }
+                    if dist[nbr] == Some(next_dist) {
+                        let paths = sigma[current];
+                        sigma[nbr] += paths;
+                        preds[nbr].push(current);
+                    }
+                }
+            }
+
+            // Hand each node's share of the paths back towards the source,
+            // farthest nodes first
+            let mut delta = Vec::from_elem(n, 0.0f64);
+            loop {
+                let current = match order.pop() {
+                    Some(x) => x,
+                    None => break,
+                };
+                for &pred in preds[current].iter() {
+                    let share = sigma[pred] / sigma[current] *
+                                (1.0 + delta[current]);
+                    delta[pred] += share;
+                }
+                if current != source {
+                    centrality[current] += delta[current];
+                }
+            }
+        }
+
+        // Every pair was counted once from each end
+        let mut scores = HashMap::<uint, f64>::new();
+        for index in range(0, n) {
+            scores.insert(index, centrality[index] / 2.0);
+        }
+        return scores;
+    }
+
+    pub fn closeness_centrality(&self) -> HashMap<uint, f64> {
+        // For every node (keyed by index), the number of other nodes it can
+        // reach divided by the total hop distance to them, i.e. one over the
+        // average distance. Nodes that reach nothing else get 0.0.
+        let mut scores = HashMap::<uint, f64>::new();
+        for index in range(0, self.nodes.len()) {
+            let dist = self.bfs_distances(index);
+            let mut total = 0u;
+            for d in dist.values() {
+                total += *d;
+            }
+            let score = if total == 0 {
+                0.0
+            } else {
+                (dist.len() - 1) as f64 / total as f64
+            };
+            scores.insert(index, score);
+        }
+        return scores;
+    }
+
+    pub fn articulation_points(&self) -> Vec<&N> {
+        // Nodes whose removal would split their component in two or more,
+        // in node order. Found with the DFS low-link method: a DFS root is
+        // one if it has two or more children, any other node if some child's
+        // subtree can't reach above it without going through it.
+        let n = self.nodes.len();
+        let (disc, low, parent) = self.low_links();
+        let mut is_cut = Vec::from_elem(n, false);
+        let mut root_children = Vec::from_elem(n, 0u);
+
+        for index in range(0, n) {
+            match parent[index] {
+                Some(p) if parent[p].is_none() => { root_children[p] += 1; }
+                Some(p) => {
+                    if low[index] >= disc[p] {
+                        is_cut[p] = true;
+                    }
+                }
+                None => {}
+            };
+        }
+
+        let mut points = Vec::<&N>::new();
+        for index in range(0, n) {
+            if is_cut[index] || root_children[index] >= 2 {
+                points.push(&self.nodes[index]);
+            }
+        }
+        return points;
+    }
+
+    pub fn bridges(&self) -> Vec<(&N, &N)> {
+        // Edges whose removal would split their component, each given
+        // lower-indexed node first. A DFS tree edge is a bridge when the
+        // child's subtree can't reach the parent or above any other way.
+        let (disc, low, parent) = self.low_links();
+        let mut keys = Vec::<(uint, uint)>::new();
+        for index in range(0, self.nodes.len()) {
+            match parent[index] {
+                Some(p) if low[index] > disc[p] => {
+                    keys.push(edge_key(p, index));
+                }
+                _ => {}
+            };
+        }
+        keys.sort();
+
+        let mut bridges = Vec::<(&N, &N)>::new();
+        for &(node1, node2) in keys.iter() {
+            bridges.push((&self.nodes[node1], &self.nodes[node2]));
+        }
+        return bridges;
+    }
+
+    pub fn greedy_coloring(&self) -> HashMap<uint, uint> {
+        // Color the nodes (keyed by node index) so that no two neighbors
+        // share a color, using the Welsh-Powell heuristic: highest degree
+        // first, each node taking the lowest color its neighbors don't have.
+        // Self-loops are ignored.
+        let mut color = HashMap::<uint, uint>::new();
+        for &index in self.indices_by_degree().iter() {
+            let mut taken = HashSet::<uint>::new();
+            for &(nbr, _) in self.adj_list[index].iter() {
+                match color.get(&nbr) {
+                    Some(&c) if nbr != index => { taken.insert(c); }
+                    _ => {}
+                };
+            }
+            let mut c = 0u;
+            while taken.contains(&c) {
+                c += 1;
+            }
+            color.insert(index, c);
+        }
+        return color;
+    }
+
+    pub fn chromatic_number_upper_bound(&self) -> uint {
+        // Number of colors greedy_coloring uses, 0 for an empty graph
+        match self.greedy_coloring().values().max() {
+            Some(&c) => c + 1,
+            None => 0,
+        }
+    }
+
+    pub fn is_bipartite(&self) -> bool {
+        // Check whether the nodes can be split into two sides with every
+        // edge going across
+        return self.bipartite_coloring().is_ok();
+    }
+
+    pub fn bipartite_coloring(&self)
+                              -> Result<HashMap<uint, u8>, GraphError> {
+        // Two-color the nodes (0 or 1, keyed by node index) so that no edge
+        // joins two nodes of the same color. Each component is colored by
+        // its own BFS. An odd cycle, or a self-loop, gives NotBipartite.
+        let mut color = HashMap::<uint, u8>::new();
+        for root in range(0, self.nodes.len()) {
+            if color.contains_key(&root) {
+                continue;
+            }
+            color.insert(root, 0);
+            let mut queue = RingBuf::<uint>::new();
+            queue.push_back(root);
+
+            loop {
+                let current = match queue.pop_front() {
+                    Some(x) => x,
+                    None => break,
+                };
+                let other_color = 1 - color[current];
+
+                for &(nbr, _) in self.adj_list[current].iter() {
+                    match color.get(&nbr) {
+                        Some(&c) if c != other_color => {
+                            return Err(GraphError::NotBipartite);
+                        }
+                        Some(_) => {}
+                        None => {
+                            color.insert(nbr, other_color);
+                            queue.push_back(nbr);
+                        }
+                    };
+                }
+            }
+        }
+        return Ok(color);
+    }
+
+    pub fn maximum_bipartite_matching(&self)
+                                      -> Result<Vec<(&N, &N)>, GraphError> {
+        // Largest set of edges with no node in common, by augmenting paths
+        // (Kuhn's algorithm). Each pair is (side 0 node, side 1 node) as
+        // given by bipartite_coloring, in index order of the side 0 node.
+        // Fails with NotBipartite if the graph can't be two-colored.
+        let color = try!(self.bipartite_coloring());
+        let n = self.nodes.len();
+        let mut mate = Vec::from_elem(n, None::<uint>);
+
+        for root in range(0, n) {
+            if color[root] != 0 {
+                continue;
+            }
+            // Depth first search for an augmenting path from root. `stack`
+            // holds the side 0 nodes on the path with the next neighbor to
+            // try, `path` the side 1 node taken from each of them.
+            let mut visited = Vec::from_elem(n, false);
+            let mut stack = vec![(root, 0u)];
+            let mut path = Vec::<uint>::new();
+            loop {
+                let (left, pos) = match stack.last() {
+                    Some(&x) => x,
+                    None => break,
+                };
+                if pos == self.adj_list[left].len() {
+                    stack.pop();
+                    path.pop();
+                    continue;
+                }
+                let top = stack.len() - 1;
+                stack[top] = (left, pos + 1);
+
+                let (right, _) = self.adj_list[left][pos];
+                if visited[right] {
+                    continue;
+                }
+                visited[right] = true;
+                path.push(right);
+                match mate[right] {
+                    Some(next) => { stack.push((next, 0)); }
+                    None => {
+                        // Free node reached: flip the path
+                        for i in range(0, path.len()) {
+                            let (l, _) = stack[i];
+                            mate[l] = Some(path[i]);
+                            mate[path[i]] = Some(l);
+                        }
+                        break;
+                    }
+                };
+            }
+        }
+
+        let mut matching = Vec::<(&N, &N)>::new();
+        for index in range(0, n) {
+            match mate[index] {
+                Some(other) if color[index] == 0 => {
+                    matching.push((&self.nodes[index], &self.nodes[other]));
+                }
+                _ => {}
+            };
+        }
+        return Ok(matching);
+    }
+
+    pub fn has_eulerian_circuit(&self) -> bool {
+        // Check for a closed walk using every edge exactly once: all degrees
+        // must be even, and all nodes with edges in one component.
+        // Isolated nodes don't matter, so a graph with no edges has one.
+        return self.odd_degree_count() == 0 && self.edges_connected();
+    }
+
+    pub fn has_eulerian_path(&self) -> bool {
+        // Check for a walk using every edge exactly once, closed or not.
+        // As for the circuit, but two nodes may have odd degree.
+        let odd = self.odd_degree_count();
+        return (odd == 0 || odd == 2) && self.edges_connected();
+    }
+
+    pub fn to_adjacency_matrix(&self) -> Vec<Vec<u8>> {
+        // Dense N x N adjacency matrix, rows and columns in node order.
+        // matrix[i][j] is 1 if nodes i and j are adjacent and 0 otherwise,
+        // so self-loops show up on the diagonal.
+        let n = self.nodes.len();
+        let mut matrix = Vec::from_elem(n, Vec::from_elem(n, 0u8));
+        for index in range(0, n) {
+            for &(nbr, _) in self.adj_list[index].iter() {
+                matrix[index][nbr] = 1;
+            }
+        }
+        return matrix;
+    }
+
+    pub fn laplacian_matrix(&self) -> Vec<Vec<i64>> {
+        // Laplacian D - A, rows and columns in node order: the degree on
+        // the diagonal and -1 wherever two nodes are adjacent. Self-loops
+        // are left out, so every row sums to zero.
+        let n = self.nodes.len();
+        let mut matrix = Vec::from_elem(n, Vec::from_elem(n, 0i64));
+        for index in range(0, n) {
+            for &(nbr, _) in self.adj_list[index].iter() {
+                if nbr != index {
+                    matrix[index][nbr] = -1;
+                    matrix[index][index] += 1;
+                }
+            }
+        }
+        return matrix;
+    }
+
+    pub fn incidence_matrix(&self) -> Vec<Vec<u8>> {
+        // Dense N x E incidence matrix: one row per node in node order, one
+        // column per edge in edges_iter order. matrix[v][e] is 1 if node v
+        // is an end of edge e, so a self-loop's column has a single 1.
+        let edges = self.weighted_edges();
+        let mut matrix = Vec::from_elem(self.nodes.len(),
+                                        Vec::from_elem(edges.len(), 0u8));
+        for (column, &(node1, node2, _)) in edges.iter().enumerate() {
+            matrix[node1][column] = 1;
+            matrix[node2][column] = 1;
+        }
+        return matrix;
+    }
+
+    pub fn from_adjacency_matrix(matrix: &[Vec<u8>], nodes: Vec<N>)
+                                 -> Result<Graph<N>, GraphError> {
+        // Build a graph from a square adjacency matrix, where row/column i
+        // belongs to nodes[i]. Only the upper triangle (and diagonal) is
+        // read, since the matrix of an undirected graph is symmetric.
+        let n = nodes.len();
+        if matrix.len() != n {
+            return Err(GraphError::DimensionMismatch);
+        }
+        for row in matrix.iter() {
+            if row.len() != n {
+                return Err(GraphError::DimensionMismatch);
+            }
+        }
+
+        let mut graph = Graph::new();
+        let indices = graph.add_nodes_multiple(nodes);
+        for i in range(0, n) {
+            for j in range(i, n) {
+                // A 1 on the diagonal is a self-loop, which is kept
+                // whatever allow_self_loops says
+                if matrix[i][j] != 0 {
+                    graph.insert_edge(indices[i], indices[j], 1.0);
+                }
+            }
+        }
+        return Ok(graph);
+    }
+
+    pub fn subgraph(&self, nodes: &[N]) -> Result<Graph<N>, GraphError> {
+        // Induced subgraph: the given nodes plus every edge between two of
+        // them. Node and edge attributes are carried over.
+        let mut indices = Vec::<uint>::new();
+        for node in nodes.iter() {
+            if !self.has_node(node) {
+                return Err(GraphError::NodeNotFound);
+            }
+            indices.push(self.get_index(node));
+        }
+        return Ok(self.induced_subgraph(indices.as_slice()));
+    }
+
+    pub fn filter_nodes<F: Fn(&N) -> bool>(&self, pred: F) -> Graph<N> {
+        // Induced subgraph of the nodes for which `pred` is true.
+        // Attributes are carried over, as with subgraph.
+        let mut keep = Vec::<uint>::new();
+        for (index, node) in self.nodes.iter().enumerate() {
+            if pred(node) {
+                keep.push(index);
+            }
+        }
+        return self.induced_subgraph(keep.as_slice());
+    }
+
+    pub fn filter_edges<F: Fn(&N, &N) -> bool>(&self, pred: F) -> Graph<N> {
+        // All the nodes, but only the edges for which `pred` is true.
+        // `pred` sees the lower-indexed node first. Attributes are kept.
+        let all = Vec::from_fn(self.nodes.len(), |index| index);
+        let mut graph = self.induced_subgraph(all.as_slice());
+        for &(node1, node2, _) in self.weighted_edges().iter() {
+            let n1 = &self.nodes[node1];
+            let n2 = &self.nodes[node2];
+            if !pred(n1, n2) {
+                let _ = graph.remove_edge(n1, n2);
+            }
+        }
+        return graph;
+    }
+
+    pub fn k_core(&self, k: uint) -> Graph<N> {
+        // Largest induced subgraph in which every node has degree >= k.
+        // Nodes below k are peeled off one at a time, which can push their
+        // neighbors below k in turn. Degrees are counted as in degree().
+        let mut degree = Vec::<uint>::new();
+        for index in range(0, self.nodes.len()) {
+            degree.push(self.adj_list[index].len());
+        }
+
+        let mut removed = Vec::from_elem(self.nodes.len(), false);
+        let mut stack = Vec::<uint>::new();
+        for index in range(0, self.nodes.len()) {
+            if degree[index] < k {
+                removed[index] = true;
+                stack.push(index);
+            }
+        }
+
+        loop {
+            let current = match stack.pop() {
+                Some(x) => x,
+                None => break,
+            };
+            for &(nbr, _) in self.adj_list[current].iter() {
+                if removed[nbr] {
+                    continue;
+                }
+                degree[nbr] -= 1;
+                if degree[nbr] < k {
+                    removed[nbr] = true;
+                    stack.push(nbr);
+                }
+            }
+        }
+
+        let mut keep = Vec::<uint>::new();
+        for index in range(0, self.nodes.len()) {
+            if !removed[index] {
+                keep.push(index);
+            }
+        }
+        return self.induced_subgraph(keep.as_slice());
+    }
+
+    pub fn union(&self, other: &Graph<N>) -> Graph<N> {
+        // New graph holding the nodes and edges of both graphs.
+        // Nodes equal by value are unified. Where both graphs set the same
+        // attribute (or weight, for edges), the value from `self` is kept.
+        let mut graph = self.clone();
+        graph.merge_in_place(other);
+        return graph;
+    }
+
+    pub fn merge_in_place(&mut self, other: &Graph<N>) {
+        // Fold the nodes and edges of `other` into this graph, like union
+        // but without building a new one. Attributes and weights self
+        // already has are kept. Self-loops in `other` come across whatever
+        // either graph's allow_self_loops says, and that setting is left
+        // as it was.
+        self.merge_from(other);
+    }
+
+    pub fn intersection(&self, other: &Graph<N>) -> Graph<N> {
+        // New graph with just the nodes, and the edges, that are in both
+        // graphs. Attributes and weights are taken from `self`.
+        let mut indices = Vec::<uint>::new();
+        for (index, node) in self.nodes.iter().enumerate() {
+            if other.has_node(node) {
+                indices.push(index);
+            }
+        }
+
+        // Start from everything self has among the shared nodes, then drop
+        // the edges that `other` doesn't have.
+        let mut graph = self.induced_subgraph(indices.as_slice());
+        for &(node1, node2, _) in self.weighted_edges().iter() {
+            let n1 = &self.nodes[node1];
+            let n2 = &self.nodes[node2];
+            if graph.has_edge(n1, n2) && !other.has_edge(n1, n2) {
+                let _ = graph.remove_edge(n1, n2);
+            }
+        }
+        return graph;
+    }
+
+    pub fn to_directed(&self) -> DiGraph<N> {
+        // DiGraph with the same nodes (and node attributes) where every
+        // edge becomes a pair of opposite directed edges of the same weight.
+        // A self-loop becomes a single edge. DiGraph has no edge attributes,
+        // so those are dropped. The name is kept.
+        let mut graph = DiGraph::new();
+        graph.set_name(self.name.clone());
+        for (index, node) in self.nodes.iter().enumerate() {
+            graph.add_node(node.clone());
+            let _ = graph.set_node_attr(node, self.attr_list[index].clone());
+        }
+        for &(node1, node2, weight) in self.weighted_edges().iter() {
+            let n1 = &self.nodes[node1];
+            let n2 = &self.nodes[node2];
+            graph.add_weighted_edge(n1, n2, weight);
+            if node1 != node2 {
+                graph.add_weighted_edge(n2, n1, weight);
+            }
+        }
+        return graph;
+    }
+
+    pub fn complement(&self) -> Graph<N> {
+        // New graph on the same nodes (and node attributes) where two
+        // distinct nodes are adjacent iff they aren't adjacent in self.
+        // The complement never has self-loops.
+        let mut graph = Graph::new();
+        graph.allow_self_loops = self.allow_self_loops;
+        for (index, node) in self.nodes.iter().enumerate() {
+            graph.add_node(node.clone());
+            graph.attr_list.insert(index, self.attr_list[index].clone());
+        }
+
+        let matrix = self.to_adjacency_matrix();
+        for i in range(0, self.nodes.len()) {
+            for j in range(i + 1, self.nodes.len()) {
+                if matrix[i][j] == 0 {
+                    graph.add_edge(&self.nodes[i], &self.nodes[j]);
+                }
+            }
+        }
+        return graph;
+    }
+
+    // Helpers from here on out
+    // To be used internally only. No public API.
+
+    fn edges(&self) -> Vec<Edge> {
+        // Return all edges of a Graph, each once, in weighted_edges order
+        let mut edge_vec = Vec::<Edge>::new();
+        for &(node1, node2, _) in self.weighted_edges().iter() {
+            edge_vec.push(Edge(node1, node2));
+        }
+        return edge_vec;
+    }
+
+
+    fn merge_from(&mut self, other: &Graph<N>) {
+        // Fold the nodes, edges and attributes of `other` into self.
+        // Anything self already has is left alone.
+        for (index, node) in other.nodes.iter().enumerate() {
+            let self_index = self.add_node(node.clone());
+            for (key, value) in other.attr_list[index].iter() {
+                if !self.attr_list[self_index].contains_key(key) {
+                    self.attr_list[self_index].insert(key.clone(), value.clone());
+                }
+            }
+        }
+
+        for &(node1, node2, weight) in other.weighted_edges().iter() {
+            let n1 = &other.nodes[node1];
+            let n2 = &other.nodes[node2];
+            let index1 = self.get_index(n1);
+            let index2 = self.get_index(n2);
+            if !self.has_edge(n1, n2) {
+                self.insert_edge(index1, index2, weight);
+            }
+            let self_key = edge_key(index1, index2);
+            let other_attrs = &other.edge_attr_list[edge_key(node1, node2)];
+            for (key, value) in other_attrs.iter() {
+                if !self.edge_attr_list[self_key].contains_key(key) {
+                    self.edge_attr_list[self_key].insert(key.clone(),
+                                                         value.clone());
+                }
+            }
+        }
+    }
+
+    fn induced_subgraph(&self, indices: &[uint]) -> Graph<N> {
+        // New graph with the nodes at `indices`, in that order, and all
+        // edges among them. Attributes and weights are copied across.
+        let mut graph = Graph::new();
+        graph.allow_self_loops = self.allow_self_loops;
+        let mut keep = HashSet::<uint>::new();
+        for &index in indices.iter() {
+            if keep.contains(&index) {
+                continue;
+            }
+            keep.insert(index);
+            let new_index = graph.add_node(self.nodes[index].clone());
+            graph.attr_list.insert(new_index, self.attr_list[index].clone());
+        }
+
+        for &(node1, node2, weight) in self.weighted_edges().iter() {
+            if keep.contains(&node1) && keep.contains(&node2) {
+                let index1 = graph.get_index(&self.nodes[node1]);
+                let index2 = graph.get_index(&self.nodes[node2]);
+                graph.insert_edge(index1, index2, weight);
+                let attrs = self.edge_attr_list[edge_key(node1, node2)].clone();
+                graph.edge_attr_list.insert(edge_key(index1, index2), attrs);
+            }
+        }
+        return graph;
+    }
+
+    fn weighted_edges(&self) -> Vec<(uint, uint, f64)> {
+        // Each edge once, as (lower index, higher index, weight)
+        // A self-loop has two entries in its node's Vec, so only take one.
+        let mut edge_vec = Vec::<(uint, uint, f64)>::new();
+        for index in range(0, self.nodes.len()) {
+            let mut loop_seen = false;
+            for &(nbr, weight) in self.adj_list[index].iter() {
+                if nbr > index {
+                    edge_vec.push((index, nbr, weight));
+                } else if nbr == index {
+                    if !loop_seen {
+                        edge_vec.push((index, nbr, weight));
+                    }
+                    loop_seen = !loop_seen;
+                }
+            }
+        }
+        return edge_vec;
+    }
+
+    fn odd_degree_count(&self) -> uint {
+        // Number of nodes of odd degree
+        let mut odd = 0u;
+        for nbrs in self.adj_list.values() {
+            if nbrs.len() % 2 == 1 {
+                odd += 1;
+            }
+        }
+        return odd;
+    }
+
+    fn edges_connected(&self) -> bool {
+        // Whether all nodes that have at least one edge are in the same
+        // component. Isolated nodes are ignored.
+        let mut with_edges = Vec::<uint>::new();
+        for index in range(0, self.nodes.len()) {
+            if !self.adj_list[index].is_empty() {
+                with_edges.push(index);
+            }
+        }
+        if with_edges.is_empty() {
+            return true;
+        }
+        return self.bfs_indices(with_edges[0]).len() == with_edges.len();
+    }
+
+    fn indices_by_degree(&self) -> Vec<uint> {
+        // Node indices, highest degree first. sort_by is stable, so ties
+        // keep their insertion order.
+        let mut indices = Vec::from_fn(self.nodes.len(), |index| index);
+        indices.sort_by(|&a, &b| {
+            self.adj_list[b].len().cmp(&self.adj_list[a].len())
+        });
+        return indices;
+    }
+
+    fn local_clustering(&self, index: uint) -> f64 {
+        // clustering_coefficient, by node index
+        let mut nbrs = Vec::<uint>::new();
+        for &(nbr, _) in self.adj_list[index].iter() {
+            if nbr != index && !nbrs.contains(&nbr) {
+                nbrs.push(nbr);
+            }
+        }
+        let k = nbrs.len();
+        if k < 2 {
+            return 0.0;
+        }
+
+        let mut links = 0u;
+        for i in range(0, k) {
+            for j in range(i + 1, k) {
+                if self.has_edge(&self.nodes[nbrs[i]], &self.nodes[nbrs[j]]) {
+                    links += 1;
+                }
+            }
+        }
+        return (2 * links) as f64 / (k * (k - 1)) as f64;
+    }
+
+    fn bfs_indices(&self, start_index: uint) -> Vec<uint> {
+        // BFS over node indices. Returns indices in visiting order.
+        let mut order = Vec::<uint>::new();
+        let mut visited = HashSet::<uint>::new();
+        let mut queue = RingBuf::<uint>::new();
+
+        visited.insert(start_index);
+        queue.push_back(start_index);
+
+        loop {
+            let current = match queue.pop_front() {
+                Some(x) => x,
+                None => break,
+            };
+            order.push(current);
+
+            for &(nbr, _) in self.adj_list[current].iter() {
+                if !visited.contains(&nbr) {
+                    visited.insert(nbr);
+                    queue.push_back(nbr);
+                }
+            }
+        }
+        return order;
+    }
+
+    fn bfs_distances(&self, start_index: uint) -> HashMap<uint, uint> {
+        // BFS over node indices, recording the hop count to each node
+        let mut dist = HashMap::<uint, uint>::new();
+        let mut queue = RingBuf::<uint>::new();
+
+        dist.insert(start_index, 0);
+        queue.push_back(start_index);
+
+        loop {
+            let current = match queue.pop_front() {
+                Some(x) => x,
+                None => break,
+            };
+            let next_dist = dist[current] + 1;
+
+            for &(nbr, _) in self.adj_list[current].iter() {
+                if !dist.contains_key(&nbr) {
+                    dist.insert(nbr, next_dist);
+                    queue.push_back(nbr);
+                }
+            }
+        }
+        return dist;
+    }
+
+    fn low_links(&self) -> (Vec<uint>, Vec<uint>, Vec<Option<uint>>) {
+        // DFS over every component, giving each node's discovery time, its
+        // low-link and its parent in the DFS forest (None for roots).
+        // low[n] is the earliest discovery time that n's subtree reaches
+        // with a single edge back up the tree.
+        let n = self.nodes.len();
+        let mut disc = Vec::from_elem(n, 0u);
+        let mut low = Vec::from_elem(n, 0u);
+        let mut parent: Vec<Option<uint>> = Vec::from_elem(n, None);
+        let mut visited = Vec::from_elem(n, false);
+        let mut time = 0u;
+
+        for root in range(0, n) {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            disc[root] = time;
+            low[root] = time;
+            time += 1;
+
+            // Explicit stack, as in dfs. Each entry is a node and the
+            // position in its adj_list of the next neighbor to look at.
+            let mut stack = vec![(root, 0u)];
+            loop {
+                let (current, pos) = match stack.pop() {
+                    Some(x) => x,
+                    None => break,
+                };
+
+                if pos < self.adj_list[current].len() {
+                    stack.push((current, pos + 1));
+                    let (nbr, _) = self.adj_list[current][pos];
+                    if !visited[nbr] {
+                        visited[nbr] = true;
+                        parent[nbr] = Some(current);
+                        disc[nbr] = time;
+                        low[nbr] = time;
+                        time += 1;
+                        stack.push((nbr, 0));
+                    } else if parent[current] != Some(nbr) &&
+                              disc[nbr] < low[current] {
+                        low[current] = disc[nbr];
+                    }
+                } else {
+                    // All neighbors done, so hand the low-link up
+                    match parent[current] {
+                        Some(p) if low[current] < low[p] => {
+                            low[p] = low[current];
+                        }
+                        _ => {}
+                    };
+                }
+            }
+        }
+        return (disc, low, parent);
+    }
+
+    fn eccentricities(&self) -> Option<Vec<uint>> {
+        // Eccentricity of every node, by index, from a BFS out of each one.
+        // None if the graph is empty or disconnected.
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let mut ecc = Vec::<uint>::new();
+        for index in range(0, self.nodes.len()) {
+            let dist = self.bfs_distances(index);
+            if dist.len() < self.nodes.len() {
+                return None;
+            }
+            ecc.push(*dist.values().max().unwrap());
+        }
+        return Some(ecc);
+    }
+
+    fn build_path(&self, parent: &HashMap<uint, uint>,
+                  start_index: uint, end_index: uint) -> Vec<&N> {
+        // Walk back from `end_index` along the parents, then flip the path
+        let mut path = vec![&self.nodes[end_index]];
+        let mut current = end_index;
+        while current != start_index {
+            current = parent[current];
+            path.push(&self.nodes[current]);
+        }
+        path.reverse();
+        return path;
+    }
+
+    fn get_index(&self, node: &N) -> uint {
+        // All nodes are unique which allows us to assign each node an index
+        // Look the index up in the node_index map
+        match self.node_index.get(node) {
+            Some(&index) => { return index; }
+            None => {}
+        };
+        // No node found. Node doesn't exist
+        // Since it is internal function, there should occur no such situation
+        // Panic.
+        panic!("Node does not exist.");
+    }
+
+
+    fn has_node(&self, node: &N) -> bool {
+        return self.node_index.contains_key(node);
+    }
+
+    fn has_edge(&self, node1: &N, node2: &N) -> bool {
+        // A missing node has no edges, so don't let get_index panic
+        if !self.has_node(node1) || !self.has_node(node2) {
+            return false;
+        }
+        let n1_ind = self.get_index(node1);
+        let n2_ind = self.get_index(node2);
+        for &(nbr, _) in self.adj_list[n1_ind].iter() {
+            if nbr == n2_ind {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    fn existing_edge_key(&self, node1: &N, node2: &N)
+                         -> Result<(uint, uint), GraphError> {
+        // edge_attr_list key of an edge, checking that the edge exists
+        if !self.has_node(node1) || !self.has_node(node2) {
+            return Err(GraphError::NodeNotFound);
+        }
+        if !self.has_edge(node1, node2) {
+            return Err(GraphError::EdgeNotFound);
+        }
+        return Ok(edge_key(self.get_index(node1), self.get_index(node2)));
+    }
+
+    fn insert_edge(&mut self, node1: uint, node2: uint, weight: f64) {
+        // Add the edge between two nodes, by index, or just set its weight
+        // if it's there already. Unlike add_weighted_edge this ignores
+        // allow_self_loops, for copying loops a graph already has (or was
+        // saved with) without changing that setting.
+        if self.adj_list[node1].iter().any(|&(nbr, _)| nbr == node2) {
+            self.set_weight(node1, node2, weight);
+            self.set_weight(node2, node1, weight);
+            return;
+        }
+        self.adj_list[node1].push((node2, weight));
+        self.adj_list[node2].push((node1, weight));
+        self.edge_attr_list.insert(edge_key(node1, node2), HashMap::new());
+    }
+
+    fn set_weight(&mut self, from: uint, to: uint, weight: f64) {
+        // Overwrite the weight stored in `from`'s entry for `to`
+        for entry in self.adj_list[from].iter_mut() {
+            let (nbr, _) = *entry;
+            if nbr == to {
+                *entry = (nbr, weight);
+            }
+        }
+    }
+}
+
+// Methods that only make sense for the built-in Node type, since they
+// need to turn nodes into (or parse them from) text.
+impl Graph<Node> {
+    pub fn to_dot(&self) -> String {
+        // Return a GraphViz (DOT) description of the Graph
+        // Node attributes are written out as [key="value", ...]
+        // A named graph is written as `graph "name" {`.
+        let mut edges = Vec::<(uint, uint)>::new();
+        for &(node1, node2, _) in self.weighted_edges().iter() {
+            edges.push((node1, node2));
+        }
+        return dot::write_graph("graph", self.name.as_slice(),
+                                self.nodes.as_slice(), &self.attr_list,
+                                edges.as_slice());
+    }
+
+    pub fn to_json(&self) -> String {
+        // Serialize the graph to JSON.
+        // Nodes are tagged with their variant ("str" or "int"). Edges are
+        // [index1, index2, weight, attrs] where the indices point into
+        // "nodes" and attrs is an object, as it is for nodes.
+        // JSON has no NaN or infinity, so those weights are written as the
+        // strings "NaN", "inf" and "-inf".
+        let mut nodes = Vec::<String>::new();
+        for (index, node) in self.nodes.iter().enumerate() {
+            let (tag, value) = match *node {
+                Node::Str(ref s) => ("str", json::encode(s)),
+                Node::Int(v) => ("int", v.to_string()),
+            };
+            let mut attrs = Vec::<String>::new();
+            for (key, val) in self.attr_list[index].iter() {
+                attrs.push(format!("{}: {}", json::encode(key),
+                                   json::encode(val)));
+            }
+            nodes.push(format!("{{\"type\": \"{}\", \"value\": {}, \"attrs\": {{{}}}}}",
+                               tag, value, attrs.connect(", ")));
+        }
+
+        let mut edges = Vec::<String>::new();
+        for &(node1, node2, weight) in self.weighted_edges().iter() {
+            let mut attrs = Vec::<String>::new();
+            for (key, val) in self.edge_attr_list[edge_key(node1, node2)]
+                                  .iter() {
+                attrs.push(format!("{}: {}", json::encode(key),
+                                   json::encode(val)));
+            }
+            edges.push(format!("[{}, {}, {}, {{{}}}]", node1, node2,
+                               json_weight(weight), attrs.connect(", ")));
+        }
+
+        return format!("{{\"name\": {}, \"nodes\": [{}], \"edges\": [{}]}}",
+                       json::encode(&self.name), nodes.connect(", "),
+                       edges.connect(", "));
+    }
+
+    pub fn to_graphml(&self) -> String {
+        // Serialize the graph to GraphML.
+        // Node ids are "n<index>". Each node carries its variant and value
+        // as "type"/"value" data, edges carry their "weight", and every
+        // node or edge attribute gets a <key> of its own ("na<i>"/"ea<i>").
+        let mut node_keys = Vec::<&String>::new();
+        for attrs in self.attr_list.values() {
+            for key in attrs.keys() {
+                if !node_keys.contains(&key) {
+                    node_keys.push(key);
+                }
+            }
+        }
+        node_keys.sort();
+        let mut edge_keys = Vec::<&String>::new();
+        for attrs in self.edge_attr_list.values() {
+            for key in attrs.keys() {
+                if !edge_keys.contains(&key) {
+                    edge_keys.push(key);
+                }
+            }
+        }
+        edge_keys.sort();
+
+        let mut xml = String::from_str(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        xml.push_str(graphml_key("type", "node", "type", "string").as_slice());
+        xml.push_str(graphml_key("value", "node", "value", "string").as_slice());
+        xml.push_str(graphml_key("weight", "edge", "weight",
+                                 "double").as_slice());
+        for (i, key) in node_keys.iter().enumerate() {
+            let id = format!("na{}", i);
+            xml.push_str(graphml_key(id.as_slice(), "node", key.as_slice(),
+                                     "string").as_slice());
+        }
+        for (i, key) in edge_keys.iter().enumerate() {
+            let id = format!("ea{}", i);
+            xml.push_str(graphml_key(id.as_slice(), "edge", key.as_slice(),
+                                     "string").as_slice());
+        }
+
+        // The graph's id is its name, and is left out for unnamed graphs
+        if self.name.is_empty() {
+            xml.push_str("  <graph edgedefault=\"undirected\">\n");
+        } else {
+            xml.push_str(format!("  <graph id=\"{}\" edgedefault=\"undirected\">\n",
+                                 xml_escape(self.name.as_slice())).as_slice());
+        }
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let tag = match *node {
+                Node::Str(_) => "str",
+                Node::Int(_) => "int",
+            };
+            let value = extract_node(node);
+            xml.push_str(format!("    <node id=\"n{}\">\n", index).as_slice());
+            xml.push_str(graphml_data("type", tag).as_slice());
+            xml.push_str(graphml_data("value", value.as_slice()).as_slice());
+            for (i, key) in node_keys.iter().enumerate() {
+                match self.attr_list[index].get(*key) {
+                    Some(val) => {
+                        let id = format!("na{}", i);
+                        xml.push_str(graphml_data(id.as_slice(),
+                                                  val.as_slice()).as_slice());
+                    }
+                    None => {}
+                };
+            }
+            xml.push_str("    </node>\n");
+        }
+
+        for &(node1, node2, weight) in self.weighted_edges().iter() {
+            xml.push_str(format!("    <edge source=\"n{}\" target=\"n{}\">\n",
+                                 node1, node2).as_slice());
+            let weight = weight.to_string();
+            xml.push_str(graphml_data("weight", weight.as_slice()).as_slice());
+            let attrs = &self.edge_attr_list[edge_key(node1, node2)];
+            for (i, key) in edge_keys.iter().enumerate() {
+                match attrs.get(*key) {
+                    Some(val) => {
+                        let id = format!("ea{}", i);
+                        xml.push_str(graphml_data(id.as_slice(),
+                                                  val.as_slice()).as_slice());
+                    }
+                    None => {}
+                };
+            }
+            xml.push_str("    </edge>\n");
+        }
+
+        xml.push_str("  </graph>\n</graphml>\n");
+        return xml;
+    }
+
+    pub fn from_graphml(text: &str) -> Result<Graph<Node>, GraphError> {
+        // Rebuild a graph from GraphML, such as the output of to_graphml.
+        // Elements other than keys, nodes, edges and data are skipped.
+        // A node without "type"/"value" data becomes a Node::Str of its id,
+        // and an edge without "weight" data gets weight 1.0. Any other data
+        // is read as an attribute, named after the attr.name of its <key>.
+        let root = match xml::parse(text) {
+            Ok(root) => root,
+            Err(line) => { return Err(GraphError::ParseError(line)); }
+        };
+        if root.name.as_slice() != "graphml" {
+            return Err(GraphError::ParseError(root.line));
+        }
+
+        let mut key_names = HashMap::<String, String>::new();
+        for key in root.children.iter() {
+            if key.name.as_slice() != "key" {
+                continue;
+            }
+            match (xml_attr(key, "id"), xml_attr(key, "attr.name")) {
+                (Some(id), Some(name)) => {
+                    key_names.insert(id.clone(), name.clone());
+                }
+                (Some(_), None) => {}
+                _ => { return Err(GraphError::ParseError(key.line)); }
+            };
+        }
+
+        let graph_elem = match root.children.iter()
+                                   .find(|e| e.name.as_slice() == "graph") {
+            Some(elem) => elem,
+            None => { return Err(GraphError::ParseError(root.line)); }
+        };
+        let mut graph = Graph::new();
+        match xml_attr(graph_elem, "id") {
+            Some(id) => { graph.name = id.clone(); }
+            None => {}
+        };
+
+        // Edges may come before the nodes they use, so read all nodes first.
+        // `ids` maps each GraphML node id to the node's index.
+        let mut ids = HashMap::<String, uint>::new();
+        for elem in graph_elem.children.iter() {
+            if elem.name.as_slice() != "node" {
+                continue;
+            }
+            let id = match xml_attr(elem, "id") {
+                Some(id) => id.clone(),
+                None => { return Err(GraphError::ParseError(elem.line)); }
+            };
+
+            let mut tag = String::from_str("str");
+            let mut value = id.clone();
+            let mut attrs = HashMap::<String, String>::new();
+            for data in elem.children.iter() {
+                if data.name.as_slice() != "data" {
+                    continue;
+                }
+                let key = match xml_attr(data, "key") {
+                    Some(key) => key,
+                    None => { return Err(GraphError::ParseError(data.line)); }
+                };
+                match key.as_slice() {
+                    "type" => { tag = data.text.clone(); }
+                    "value" => { value = data.text.clone(); }
+                    _ => {
+                        attrs.insert(graphml_attr_name(&key_names, key),
+                                     data.text.clone());
+                    }
+                };
+            }
+
+            let node = match tag.as_slice() {
+                "str" => Node::Str(value),
+                "int" => match from_str::<int>(value.as_slice().trim()) {
+                    Some(v) => Node::Int(v),
+                    None => { return Err(GraphError::ParseError(elem.line)); }
+                },
+                _ => { return Err(GraphError::ParseError(elem.line)); }
+            };
+            if graph.has_node(&node) || ids.contains_key(&id) {
+                return Err(GraphError::ParseError(elem.line));
+            }
+            let index = graph.add_node(node);
+            graph.attr_list.insert(index, attrs);
+            ids.insert(id, index);
+        }
+
+        for elem in graph_elem.children.iter() {
+            if elem.name.as_slice() != "edge" {
+                continue;
+            }
+            let source = xml_attr(elem, "source").and_then(|s| ids.get(s));
+            let target = xml_attr(elem, "target").and_then(|t| ids.get(t));
+            let (node1, node2) = match (source, target) {
+                (Some(&node1), Some(&node2)) => (node1, node2),
+                _ => { return Err(GraphError::ParseError(elem.line)); }
+            };
+
+            let mut weight = 1.0;
+            let mut attrs = HashMap::<String, String>::new();
+            for data in elem.children.iter() {
+                if data.name.as_slice() != "data" {
+                    continue;
+                }
+                let key = match xml_attr(data, "key") {
+                    Some(key) => key,
+                    None => { return Err(GraphError::ParseError(data.line)); }
+                };
+                if key.as_slice() == "weight" {
+                    weight = match from_str::<f64>(data.text.as_slice().trim()) {
+                        Some(w) => w,
+                        None => { return Err(GraphError::ParseError(data.line)); }
+                    };
+                } else {
+                    attrs.insert(graphml_attr_name(&key_names, key),
+                                 data.text.clone());
+                }
+            }
+
+            // Self-loops the graph was saved with are kept
+            graph.insert_edge(node1, node2, weight);
+            graph.edge_attr_list.insert(edge_key(node1, node2), attrs);
+        }
+        return Ok(graph);
+    }
+
+    pub fn from_json(s: &str) -> Result<Graph<Node>, GraphError> {
+        // Rebuild a graph from the output of to_json.
+        // An edge without a weight ([index1, index2]) gets weight 1.0, and
+        // one without attrs gets none.
+        let data = match json::from_str(s) {
+            Ok(data) => data,
+            Err(json::ParserError::SyntaxError(_, line, _)) => {
+                return Err(GraphError::ParseError(line));
+            }
+            Err(_) => { return Err(GraphError::ParseError(0)); }
+        };
+
+        let mut graph = Graph::new();
+        match data.find("name").and_then(|name| name.as_string()) {
+            Some(name) => { graph.name = name.to_string(); }
+            None => { return Err(GraphError::ParseError(0)); }
+        };
+
+        let nodes = match data.find("nodes").and_then(|n| n.as_array()) {
+            Some(nodes) => nodes,
+            None => { return Err(GraphError::ParseError(0)); }
+        };
+        for entry in nodes.iter() {
+            let (node, attrs) = match json_node(entry) {
+                Some(x) => x,
+                None => { return Err(GraphError::ParseError(0)); }
+            };
+            // Edges refer to nodes by position, so duplicates can't be merged
+            if graph.has_node(&node) {
+                return Err(GraphError::ParseError(0));
+            }
+            let index = graph.add_node(node);
+            graph.attr_list.insert(index, attrs);
+        }
+
+        let edges = match data.find("edges").and_then(|e| e.as_array()) {
+            Some(edges) => edges,
+            None => { return Err(GraphError::ParseError(0)); }
+        };
+        for entry in edges.iter() {
+            let (node1, node2, weight, attrs) = match json_edge(entry) {
+                Some(x) => x,
+                None => { return Err(GraphError::ParseError(0)); }
+            };
+            if node1 >= graph.nodes.len() || node2 >= graph.nodes.len() {
+                return Err(GraphError::ParseError(0));
+            }
+            // Self-loops the graph was saved with are kept
+            graph.insert_edge(node1, node2, weight);
+            graph.edge_attr_list.insert(edge_key(node1, node2), attrs);
+        }
+        return Ok(graph);
+    }
+
+    pub fn to_edge_list(&self) -> String {
+        // Write the graph in the format read by from_edge_list: one "a b"
+        // line per edge, lower-indexed node first, in index order.
+        // Nodes without edges get a line of their own so they aren't lost.
+        // A Str label that would read back as something else (a number,
+        // a comment, or more than one token) is quoted, so any label
+        // without a line break round-trips. Weights and attributes aren't
+        // written.
+        let mut text = String::new();
+        for index in range(0, self.nodes.len()) {
+            if self.adj_list[index].is_empty() {
+                text.push_str(edge_list_label(&self.nodes[index]).as_slice());
+                text.push_str("\n");
+            }
+        }
+        for &(node1, node2, _) in self.weighted_edges().iter() {
+            let label1 = edge_list_label(&self.nodes[node1]);
+            let label2 = edge_list_label(&self.nodes[node2]);
+            text.push_str(format!("{} {}\n", label1, label2).as_slice());
+        }
+        return text;
+    }
+
+    pub fn to_csv(&self) -> String {
+        // The adjacency matrix as CSV, for spreadsheets: a header row of
+        // node labels, then one row of 0s and 1s per node, both in node
+        // order. Labels are quoted only when they need it.
+        let mut labels = Vec::<String>::new();
+        for node in self.nodes.iter() {
+            labels.push(csv_field(extract_node(node).as_slice()));
+        }
+        let mut csv = labels.connect(",");
+        csv.push_str("\n");
+
+        for row in self.to_adjacency_matrix().iter() {
+            let cells: Vec<String> = row.iter().map(|x| x.to_string())
+                                        .collect();
+            csv.push_str(cells.connect(",").as_slice());
+            csv.push_str("\n");
+        }
+        return csv;
+    }
+
+    pub fn from_edge_list(text: &str) -> Result<Graph<Node>, GraphError> {
+        // Build a graph from lines of whitespace separated pairs, "a b".
+        // Numeric tokens become Node::Int, anything else Node::Str. A token
+        // in double quotes is always a Str and may hold whitespace; a quote
+        // inside it is doubled, as in CSV.
+        // Blank lines and lines starting with '#' are skipped, and a line
+        // with a single token adds just that node.
+        let mut graph = Graph::new();
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("#") {
+                continue;
+            }
+
+            // Line numbers are reported starting from 1
+            let mut tokens = match edge_list_tokens(line) {
+                Some(tokens) => tokens,
+                None => { return Err(GraphError::ParseError(line_no + 1)); }
+            };
+            match tokens.len() {
+                1 => {
+                    graph.add_node(tokens.pop().unwrap());
+                }
+                2 => {
+                    // "a a" asks for a self-loop, so let it through
+                    let node2 = tokens.pop().unwrap();
+                    let node1 = tokens.pop().unwrap();
+                    let index1 = graph.add_node(node1);
+                    let index2 = graph.add_node(node2);
+                    graph.insert_edge(index1, index2, 1.0);
+                }
+                _ => { return Err(GraphError::ParseError(line_no + 1)); }
+            };
+        }
+        return Ok(graph);
+    }
+
+    pub fn node_label(&self, node: &Node) -> Option<String> {
+        // Name of a node as used in the text formats: a Str as it is, an
+        // Int in decimal. None if the node isn't in the graph.
+        if !self.has_node(node) {
+            return None;
+        }
+        return Some(extract_node(node));
+    }
+}
+
+
+fn edge_key(node1: uint, node2: uint) -> (uint, uint) {
+    // Undirected edges are stored under (lower index, higher index)
+    if node1 <= node2 { (node1, node2) } else { (node2, node1) }
+}
+
+fn find_root(parent: &mut Vec<uint>, index: uint) -> uint {
+    // Union-find lookup, pointing every node on the way straight at the root
+    let mut root = index;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut current = index;
+    while parent[current] != root {
+        let next = parent[current];
+        parent[current] = root;
+        current = next;
+    }
+    return root;
+}
+
+fn bron_kerbosch(nbrs: &[HashSet<uint>], clique: &mut Vec<uint>,
+                 mut candidates: Vec<uint>, mut excluded: Vec<uint>,
+                 found: &mut Vec<Vec<uint>>) {
+    // Report every maximal clique that extends `clique` with nodes from
+    // `candidates` and none from `excluded`
+    if candidates.is_empty() {
+        if excluded.is_empty() {
+            found.push(clique.clone());
+        }
+        return;
+    }
+
+    // Pivot on the node with the most neighbors among the candidates;
+    // only nodes not adjacent to it need to be branched on
+    let mut pivot = candidates[0];
+    let mut most = 0u;
+    for &u in candidates.iter().chain(excluded.iter()) {
+        let count = candidates.iter().filter(|v| nbrs[u].contains(*v))
+                              .count();
+        if count > most {
+            pivot = u;
+            most = count;
+        }
+    }
+    let branch: Vec<uint> = candidates.iter()
+                                      .filter(|v| !nbrs[pivot].contains(*v))
+                                      .map(|&v| v).collect();
+
+    for &v in branch.iter() {
+        let next_candidates = candidates.iter()
+                                        .filter(|u| nbrs[v].contains(*u))
+                                        .map(|&u| u).collect();
+        let next_excluded = excluded.iter()
+                                    .filter(|u| nbrs[v].contains(*u))
+                                    .map(|&u| u).collect();
+        clique.push(v);
+        bron_kerbosch(nbrs, clique, next_candidates, next_excluded, found);
+        clique.pop();
+        candidates.retain(|&u| u != v);
+        excluded.push(v);
+    }
+}
+
+fn json_node(entry: &Json) -> Option<(Node, HashMap<String, String>)> {
+    // Read one entry of the "nodes" array written by to_json
+    let value = match entry.find("value") {
+        Some(value) => value,
+        None => { return None; }
+    };
+    let node = match entry.find("type").and_then(|t| t.as_string()) {
+        Some("str") => match value.as_string() {
+            Some(s) => Node::Str(s.to_string()),
+            None => { return None; }
+        },
+        Some("int") => match value.as_i64() {
+            Some(v) => Node::Int(v as int),
+            None => { return None; }
+        },
+        _ => { return None; }
+    };
+
+    return match json_attrs(entry.find("attrs")) {
+        Some(attrs) => Some((node, attrs)),
+        None => None,
+    };
+}
+
+fn json_edge(entry: &Json)
+             -> Option<(uint, uint, f64, HashMap<String, String>)> {
+    // Read one entry of the "edges" array written by to_json
+    let pair = match entry.as_array() {
+        Some(pair) => pair,
+        None => { return None; }
+    };
+    if pair.len() < 2 || pair.len() > 4 {
+        return None;
+    }
+    let (node1, node2) = match (pair[0].as_u64(), pair[1].as_u64()) {
+        (Some(node1), Some(node2)) => (node1 as uint, node2 as uint),
+        _ => { return None; }
+    };
+    if pair.len() == 2 {
+        return Some((node1, node2, 1.0, HashMap::new()));
+    }
+    let weight = match pair[2].as_string() {
+        Some("NaN") => Float::nan(),
+        Some("inf") => Float::infinity(),
+        Some("-inf") => Float::neg_infinity(),
+        Some(_) => { return None; }
+        None => match pair[2].as_f64() {
+            Some(weight) => weight,
+            None => { return None; }
+        },
+    };
+    return match json_attrs(pair.get(3)) {
+        Some(attrs) => Some((node1, node2, weight, attrs)),
+        None => None,
+    };
+}
+
+fn json_attrs(attrs: Option<&Json>) -> Option<HashMap<String, String>> {
+    // Read the "attrs" of a node or edge entry. A missing one is empty;
+    // anything but an object of strings is an error.
+    let mut map = HashMap::<String, String>::new();
+    let object = match attrs {
+        Some(attrs) => match attrs.as_object() {
+            Some(object) => object,
+            None => { return None; }
+        },
+        None => { return Some(map); }
+    };
+    for (key, val) in object.iter() {
+        match val.as_string() {
+            Some(val) => { map.insert(key.clone(), val.to_string()); }
+            None => { return None; }
+        };
+    }
+    return Some(map);
+}
+
+fn json_weight(weight: f64) -> String {
+    // Edge weight as written by to_json: a number if it's finite,
+    // otherwise one of the strings json_edge reads back
+    if weight.is_nan() {
+        return String::from_str("\"NaN\"");
+    }
+    if weight.is_infinite() {
+        let sign = if weight < 0.0 { "-" } else { "" };
+        return format!("\"{}inf\"", sign);
+    }
+    return weight.to_string();
+}
+
+fn edge_list_node(token: &str) -> Node {
+    // Numeric tokens are Int nodes, everything else is a Str node
+    match from_str::<int>(token) {
+        Some(v) => Node::Int(v),
+        None => Node::Str(token.to_string()),
+    }
+}
+
+fn edge_list_tokens(line: &str) -> Option<Vec<Node>> {
+    // Split one line of an edge list into nodes: bare tokens go through
+    // edge_list_node, quoted ones are Str. None for an unterminated quote
+    // or a closing quote not followed by whitespace.
+    let mut tokens = Vec::<Node>::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        let next = chars.peek().map(|c| *c);
+        match next {
+            None => break,
+            Some(c) if c.is_whitespace() => { chars.next(); }
+            Some('"') => {
+                chars.next();
+                let mut label = String::new();
+                loop {
+                    match chars.next() {
+                        None => { return None; }
+                        Some('"') => {
+                            // A doubled quote stands for one quote
+                            if chars.peek().map(|c| *c) != Some('"') {
+                                break;
+                            }
+                            chars.next();
+                            label.push('"');
+                        }
+                        Some(c) => { label.push(c); }
+                    };
+                }
+                match chars.peek().map(|c| *c) {
+                    Some(c) if !c.is_whitespace() => { return None; }
+                    _ => {}
+                };
+                tokens.push(Node::Str(label));
+            }
+            Some(_) => {
+                let mut token = String::new();
+                loop {
+                    match chars.peek().map(|c| *c) {
+                        Some(c) if !c.is_whitespace() => {
+                            token.push(c);
+                            chars.next();
+                        }
+                        _ => break,
+                    };
+                }
+                tokens.push(edge_list_node(token.as_slice()));
+            }
+        };
+    }
+    return Some(tokens);
+}
+
+fn edge_list_label(node: &Node) -> String {
+    // Label of a node as to_edge_list writes it. A Str is quoted whenever
+    // edge_list_tokens would otherwise read it back differently.
+    let label = extract_node(node);
+    let quote = match *node {
+        Node::Int(_) => false,
+        Node::Str(ref s) => {
+            s.is_empty() || s.starts_with("#") || s.starts_with("\"") ||
+            s.chars().any(|c| c.is_whitespace()) ||
+            from_str::<int>(s.as_slice()).is_some()
+        }
+    };
+    if !quote {
+        return label;
+    }
+    return format!("\"{}\"", label.replace("\"", "\"\""));
+}
+
+fn graphml_key(id: &str, domain: &str, name: &str, kind: &str) -> String {
+    // One <key> declaration line of the GraphML header
+    return format!("  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" \
+                    attr.type=\"{}\"/>\n", id, domain, xml_escape(name), kind);
+}
+
+fn graphml_data(key: &str, value: &str) -> String {
+    // One <data> line inside a GraphML node or edge
+    return format!("      <data key=\"{}\">{}</data>\n",
+                   key, xml_escape(value));
+}
+
+fn xml_attr<'a>(elem: &'a Element, name: &str) -> Option<&'a String> {
+    // Look up an XML attribute of an element
+    return elem.attrs.get(&name.to_string());
+}
+
+fn graphml_attr_name(key_names: &HashMap<String, String>, key: &String)
+                     -> String {
+    // Attribute name for a <data> key: the attr.name declared for it, or
+    // the key itself if it wasn't declared
+    match key_names.get(key) {
+        Some(name) => name.clone(),
+        None => key.clone(),
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    // Escape text for use in XML content or a double-quoted attribute
+    return s.replace("&", "&amp;").replace("<", "&lt;").replace(">", "&gt;")
+            .replace("\"", "&quot;");
+}
+
+fn csv_field(s: &str) -> String {
+    // Quote a CSV field if it holds a comma, quote or line break,
+    // doubling any quotes inside it
+    if s.contains_char(',') || s.contains_char('"') ||
+       s.contains_char('\n') || s.contains_char('\r') {
+        return format!("\"{}\"", s.replace("\"", "\"\""));
+    }
+    return s.to_string();
+}
+
+
+// Written out by hand: deriving would only require N: Clone, but cloning
+// the node_index map needs N: Eq + Hash as well.
+impl<N: Eq + Hash + Clone> Clone for Graph<N> {
+    fn clone(&self) -> Graph<N> {
+        // Deep copy - the clone shares nothing with the original
+        Graph {
+            nodes: self.nodes.clone(),
+            attr_list: self.attr_list.clone(),
+            adj_list: self.adj_list.clone(),
+            node_index: self.node_index.clone(),
+            edge_attr_list: self.edge_attr_list.clone(),
+            name: self.name.clone(),
+            allow_self_loops: self.allow_self_loops,
+        }
+    }
+}
+
+impl<N: Eq + Hash + Clone> PartialEq for Graph<N> {
+    fn eq(&self, other: &Graph<N>) -> bool {
+        // Equal when both have the same nodes, edges (with weights) and
+        // attributes, no matter what order they were added in.
+        // Graph names and the self-loop setting aren't compared. Two NaN
+        // weights count as equal, so a graph always equals itself.
+        if self.nodes.len() != other.nodes.len() ||
+           self.edge_count() != other.edge_count() {
+            return false;
+        }
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            if !other.has_node(node) {
+                return false;
+            }
+            if self.attr_list[index] != other.attr_list[other.get_index(node)] {
+                return false;
+            }
+        }
+
+        // Indices differ between the two, so match edges up by their nodes.
+        // With equal edge counts, finding all of ours in `other` is enough.
+        for &(node1, node2, weight) in self.weighted_edges().iter() {
+            let n1 = &self.nodes[node1];
+            let n2 = &self.nodes[node2];
+            match other.edge_weight(n1, n2) {
+                Ok(other_weight) if other_weight == weight ||
+                                    (other_weight.is_nan() &&
+                                     weight.is_nan()) => {}
+                _ => { return false; }
+            };
+
+            let other_key = edge_key(other.get_index(n1), other.get_index(n2));
+            if self.edge_attr_list[edge_key(node1, node2)] !=
+               other.edge_attr_list[other_key] {
+                return false;
+            }
+        }
+        return true;
+    }
+}
+
+// Collecting (node, node) pairs adds an edge for each pair
+impl<N: Eq + Hash + Clone> FromIterator<(N, N)> for Graph<N> {
+    fn from_iter<T: Iterator<(N, N)>>(iterator: T) -> Graph<N> {
+        let mut graph = Graph::new();
+        for (node1, node2) in iterator {
+            graph.add_edge(&node1, &node2);
+        }
+        return graph;
+    }
+}
+
+// Extending with nodes adds each one that isn't there yet
+impl<N: Eq + Hash + Clone> Extend<N> for Graph<N> {
+    fn extend<T: Iterator<N>>(&mut self, iterator: T) {
+        for node in iterator {
+            self.add_node(node);
+        }
+    }
+}
+
+// Extending with (node, node) pairs adds an edge for each pair
+impl<N: Eq + Hash + Clone> Extend<(N, N)> for Graph<N> {
+    fn extend<T: Iterator<(N, N)>>(&mut self, iterator: T) {
+        for (node1, node2) in iterator {
+            self.add_edge(&node1, &node2);
+        }
+    }
+}
+
+impl<N: Eq + Hash + Clone + Show> Show for Graph<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Shows textual repr of Graph
+        try!(write!(f, "{{ Nodes: "));
+        for n in self.nodes.iter() {
+            try!(write!(f, "{}, ", n));
+        }
+        try!(writeln!(f, ""));
+        try!(write!(f, "Edges: "));
+        for edge in self.edges().iter() {
+            try!(write!(f, "{}, ", edge));
+        }
+        write!(f, "}}")
+    }
+}
+
+impl<'a, N> Iterator<&'a N> for NodeIter<'a, N> {
+    fn next(&mut self) -> Option<&'a N> {
+        self.iter.next()
+    }
+}
+
+impl<'a, N> Iterator<(&'a N, &'a N)> for EdgeIter<'a, N> {
+    fn next(&mut self) -> Option<(&'a N, &'a N)> {
+        match self.edges.next() {
+            Some((node1, node2, _)) => {
+                Some((&self.nodes[node1], &self.nodes[node2]))
+            }
+            None => None,
+        }
+    }
+}
+
+impl Eq for HeapEntry {}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &HeapEntry) -> Option<Ordering> {
+        other.cost.partial_cmp(&self.cost)
+    }
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &HeapEntry) -> Ordering {
+        match self.partial_cmp(other) {
+            Some(ordering) => ordering,
+            None => Ordering::Equal,
+        }
+    }
+}
+
+impl Show for Edge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Edge(node1, node2) = *self;
+        write!(f, "{}--{}", node1, node2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ascii::AsciiExt;
+    use std::collections::HashMap;
+    use std::num::Float;
+    use super::{Graph, Node};
+    use super::super::xml;
+    use super::super::super::errors::GraphError;
+    use time;
+
+    fn s(label: &str) -> Node {
+        Node::Str(label.to_string())
+    }
+
+    fn graph_of(edges: &[(int, int)]) -> Graph<Node> {
+        // Int-labelled graph with the given edges, added in order
+        let mut graph = Graph::new();
+        for &(a, b) in edges.iter() {
+            graph.add_edge(&Node::Int(a), &Node::Int(b));
+        }
+        return graph;
+    }
+
+    #[test]
+    fn add_node_keys_maps_on_its_index() {
+        let mut graph = Graph::new();
+        graph.add_node(s("a"));
+        graph.add_node(s("b"));
+        graph.add_node(s("c"));
+
+        let mut keys: Vec<uint> = graph.adj_list.keys().map(|&k| k).collect();
+        keys.sort();
+        assert_eq!(keys, vec![0, 1, 2]);
+        for index in range(0u, 3) {
+            assert!(graph.adj_list[index].is_empty());
+            assert!(graph.attr_list[index].is_empty());
+        }
+    }
+
+    #[test]
+    fn add_node_returns_index_of_added_node() {
+        let mut graph = Graph::new();
+        let index = graph.add_node(Node::Int(5));
+        assert_eq!(graph.node_at(index), Some(&Node::Int(5)));
+    }
+
+    #[test]
+    fn add_nodes_multiple_returns_indices() {
+        let mut graph = Graph::new();
+        let indices = graph.add_nodes_multiple(vec![s("a"), s("b"), s("a"),
+                                                    s("c"), s("b")]);
+        assert_eq!(indices, vec![0, 1, 0, 2, 1]);
+        assert_eq!(graph.node_count(), 3);
+    }
+
+    #[test]
+    fn remove_edge_keeps_both_nodes() {
+        let mut graph = graph_of(&[(1, 2)]);
+        graph.set_node_attr_value(&Node::Int(1), "colour".to_string(),
+                                  "red".to_string()).unwrap();
+        assert_eq!(graph.remove_edge(&Node::Int(1), &Node::Int(2)), Ok(()));
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(graph.node_count(), 2);
+        assert!(!graph.contains_edge(&Node::Int(2), &Node::Int(1)));
+        assert_eq!(graph.get_node_attr_value(&Node::Int(1), "colour"),
+                   Some(&"red".to_string()));
+    }
+
+    #[test]
+    fn remove_missing_edge() {
+        let mut graph = graph_of(&[(1, 2), (2, 3)]);
+        assert_eq!(graph.remove_edge(&Node::Int(1), &Node::Int(3)),
+                   Err(GraphError::EdgeNotFound));
+        assert_eq!(graph.remove_edge(&Node::Int(1), &Node::Int(9)),
+                   Err(GraphError::NodeNotFound));
+        assert_eq!(graph.edge_count(), 2);
+    }
+
+    #[test]
+    fn remove_one_of_several_edges() {
+        let mut graph = graph_of(&[(0, 1), (0, 2), (0, 3)]);
+        graph.remove_edge(&Node::Int(0), &Node::Int(2)).unwrap();
+        assert_eq!(graph.neighbors(&Node::Int(0)).unwrap(),
+                   vec![&Node::Int(1), &Node::Int(3)]);
+        assert!(graph.neighbors(&Node::Int(2)).unwrap().is_empty());
+        assert_eq!(graph.edge_count(), 2);
+    }
+
+    #[test]
+    fn neighbors_of_star_center() {
+        let graph = graph_of(&[(0, 1), (0, 2), (0, 3)]);
+        assert_eq!(graph.neighbors(&Node::Int(0)).unwrap(),
+                   vec![&Node::Int(1), &Node::Int(2), &Node::Int(3)]);
+        assert_eq!(graph.neighbors(&Node::Int(1)).unwrap(),
+                   vec![&Node::Int(0)]);
+        assert_eq!(graph.neighbors(&Node::Int(7)),
+                   Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn triangle_degrees() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 0)]);
+        for v in range(0i, 3) {
+            assert_eq!(graph.degree(&Node::Int(v)), Ok(2));
+        }
+        assert_eq!(graph.degree(&Node::Int(3)), Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn counts_of_empty_graph() {
+        let graph: Graph<Node> = Graph::new();
+        assert_eq!(graph.node_count(), 0);
+        assert_eq!(graph.edge_count(), 0);
+    }
+
+    #[test]
+    fn counts_with_isolated_nodes() {
+        let mut graph = Graph::new();
+        graph.add_nodes_multiple(vec![s("a"), s("b"), s("c")]);
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 0);
+    }
+
+    #[test]
+    fn counts_of_cycle() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 4);
+    }
+
+    #[test]
+    fn bfs_visits_tree_in_level_order() {
+        let graph = graph_of(&[(0, 1), (0, 2), (1, 3), (1, 4), (2, 5)]);
+        let order = graph.bfs(&Node::Int(0)).unwrap();
+        let expected: Vec<Node> = range(0i, 6).map(|v| Node::Int(v)).collect();
+        assert_eq!(order, expected.iter().collect::<Vec<&Node>>());
+    }
+
+    #[test]
+    fn bfs_skips_other_components() {
+        let mut graph = graph_of(&[(0, 1), (1, 2)]);
+        graph.add_edge(&s("x"), &s("y"));
+        let order = graph.bfs(&Node::Int(1)).unwrap();
+        assert_eq!(order.len(), 3);
+        assert!(!order.contains(&&s("x")));
+        assert_eq!(graph.bfs(&s("z")), Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn dfs_on_chain() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(graph.dfs(&Node::Int(0)).unwrap(),
+                   vec![&Node::Int(0), &Node::Int(1), &Node::Int(2),
+                        &Node::Int(3)]);
+    }
+
+    #[test]
+    fn dfs_on_diamond_is_preorder() {
+        // 0 - 1 - 3 and 0 - 2 - 3: 3 is reached through 1 before 2 is
+        let graph = graph_of(&[(0, 1), (0, 2), (1, 3), (2, 3)]);
+        assert_eq!(graph.dfs(&Node::Int(0)).unwrap(),
+                   vec![&Node::Int(0), &Node::Int(1), &Node::Int(3),
+                        &Node::Int(2)]);
+    }
+
+    #[test]
+    fn components_of_two_triangles_and_a_node() {
+        let mut graph = graph_of(&[(0, 1), (1, 2), (2, 0),
+                                   (3, 4), (4, 5), (5, 3)]);
+        graph.add_node(Node::Int(6));
+        let sizes: Vec<uint> = graph.connected_components().iter()
+                                    .map(|c| c.len()).collect();
+        assert_eq!(sizes, vec![3, 3, 1]);
+    }
+
+    #[test]
+    fn shortest_path_to_neighbor() {
+        let graph = graph_of(&[(0, 1), (1, 2)]);
+        assert_eq!(graph.shortest_path(&Node::Int(0), &Node::Int(1)),
+                   Ok(Some(vec![&Node::Int(0), &Node::Int(1)])));
+    }
+
+    #[test]
+    fn shortest_path_over_several_hops() {
+        // The long way round 0 - 4 - 5 - 6 - 3 is one hop longer
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 3),
+                               (0, 4), (4, 5), (5, 6), (6, 3)]);
+        assert_eq!(graph.shortest_path(&Node::Int(0), &Node::Int(3)),
+                   Ok(Some(vec![&Node::Int(0), &Node::Int(1), &Node::Int(2),
+                                &Node::Int(3)])));
+    }
+
+    #[test]
+    fn shortest_path_between_components() {
+        let graph = graph_of(&[(0, 1), (2, 3)]);
+        assert_eq!(graph.shortest_path(&Node::Int(0), &Node::Int(3)),
+                   Ok(None));
+        assert_eq!(graph.shortest_path(&Node::Int(0), &Node::Int(9)),
+                   Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn tree_has_no_cycle() {
+        let graph = graph_of(&[(0, 1), (0, 2), (2, 3)]);
+        assert!(!graph.has_cycle());
+    }
+
+    #[test]
+    fn triangle_has_cycle() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 0)]);
+        assert!(graph.has_cycle());
+    }
+
+    #[test]
+    fn self_loop_is_cycle() {
+        let mut graph = Graph::new();
+        graph.allow_self_loops(true);
+        graph.add_edge(&s("a"), &s("a"));
+        assert!(graph.has_cycle());
+    }
+
+    #[test]
+    fn edge_weights() {
+        let mut graph = Graph::new();
+        graph.add_weighted_edge(&s("a"), &s("b"), 2.5);
+        graph.add_edge(&s("b"), &s("c"));
+        assert_eq!(graph.edge_weight(&s("a"), &s("b")), Ok(2.5));
+        assert_eq!(graph.edge_weight(&s("b"), &s("a")), Ok(2.5));
+        assert_eq!(graph.edge_weight(&s("c"), &s("b")), Ok(1.0));
+        assert_eq!(graph.edge_weight(&s("a"), &s("c")),
+                   Err(GraphError::EdgeNotFound));
+        assert_eq!(graph.edge_weight(&s("a"), &s("z")),
+                   Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn add_edge_keeps_existing_weight() {
+        let mut graph = Graph::new();
+        graph.add_weighted_edge(&s("a"), &s("b"), 2.5);
+        graph.add_edge(&s("a"), &s("b"));
+        assert_eq!(graph.edge_weight(&s("a"), &s("b")), Ok(2.5));
+        assert_eq!(graph.edge_count(), 1);
+
+        // add_weighted_edge is the way to change it
+        graph.add_weighted_edge(&s("b"), &s("a"), 4.0);
+        assert_eq!(graph.edge_weight(&s("a"), &s("b")), Ok(4.0));
+        assert_eq!(graph.edge_count(), 1);
+    }
+
+    #[test]
+    fn dijkstra_prefers_cheaper_longer_path() {
+        let mut graph = Graph::new();
+        graph.add_weighted_edge(&Node::Int(0), &Node::Int(3), 10.0);
+        graph.add_weighted_edge(&Node::Int(0), &Node::Int(1), 1.0);
+        graph.add_weighted_edge(&Node::Int(1), &Node::Int(2), 1.0);
+        graph.add_weighted_edge(&Node::Int(2), &Node::Int(3), 1.5);
+        assert_eq!(graph.dijkstra(&Node::Int(0), &Node::Int(3)),
+                   Ok(Some((vec![&Node::Int(0), &Node::Int(1), &Node::Int(2),
+                                 &Node::Int(3)], 3.5))));
+    }
+
+    #[test]
+    fn dijkstra_to_unreachable_node() {
+        let graph = graph_of(&[(0, 1), (2, 3)]);
+        assert_eq!(graph.dijkstra(&Node::Int(0), &Node::Int(3)), Ok(None));
+    }
+
+    #[test]
+    fn dijkstra_rejects_negative_weights() {
+        let mut graph = graph_of(&[(0, 1)]);
+        graph.add_weighted_edge(&Node::Int(1), &Node::Int(2), -1.0);
+        assert_eq!(graph.dijkstra(&Node::Int(0), &Node::Int(2)),
+                   Err(GraphError::NegativeWeight));
+    }
+
+    #[test]
+    fn edge_insertion_on_ten_thousand_nodes() {
+        // A path over 10k nodes. With the linear get_index this was about
+        // 10^8 comparisons and took seconds; with node_index it's a blink,
+        // so a full second is a generous bound.
+        let mut graph = Graph::new();
+        for i in range(0i, 10000) {
+            graph.add_node(Node::Int(i));
+        }
+        let start = time::precise_time_ns();
+        for i in range(0i, 9999) {
+            graph.add_edge(&Node::Int(i), &Node::Int(i + 1));
+        }
+        let elapsed = time::precise_time_ns() - start;
+        assert!(elapsed < 1_000_000_000, "took {}ns", elapsed);
+        assert_eq!(graph.node_count(), 10000);
+        assert_eq!(graph.edge_count(), 9999);
+        assert!(graph.contains_edge(&Node::Int(5000), &Node::Int(5001)));
+        assert_eq!(graph.get_index(&Node::Int(9999)), 9999);
+    }
+
+    #[test]
+    fn node_index_follows_remove_node() {
+        let mut graph = graph_of(&[(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(graph.remove_node(&Node::Int(1)), Ok(Node::Int(1)));
+
+        // swap_remove moved 3 into index 1
+        assert_eq!(graph.node_index.len(), 3);
+        assert!(!graph.contains_node(&Node::Int(1)));
+        assert_eq!(graph.get_index(&Node::Int(0)), 0);
+        assert_eq!(graph.get_index(&Node::Int(3)), 1);
+        assert_eq!(graph.get_index(&Node::Int(2)), 2);
+        for (index, node) in graph.nodes.iter().enumerate() {
+            assert_eq!(graph.node_index[*node], index);
+        }
+
+        // Edges still join the right nodes after reindexing
+        assert!(graph.contains_edge(&Node::Int(2), &Node::Int(3)));
+        assert!(!graph.contains_edge(&Node::Int(0), &Node::Int(2)));
+        assert_eq!(graph.degree(&Node::Int(0)), Ok(0));
+
+        // Removing the last node moves nothing
+        assert_eq!(graph.remove_node(&Node::Int(2)), Ok(Node::Int(2)));
+        assert_eq!(graph.get_index(&Node::Int(3)), 1);
+        assert_eq!(graph.node_index.len(), 2);
+    }
+
+    #[test]
+    fn to_dot_lists_nodes_then_edges() {
+        let mut graph = Graph::new();
+        graph.add_edge(&s("a"), &s("b"));
+        graph.allow_self_loops(true);
+        graph.add_edge(&s("c"), &s("c"));
+        graph.set_node_attr_value(&s("a"), "colour".to_string(),
+                                  "red".to_string()).unwrap();
+        graph.set_node_attr_value(&s("a"), "shape".to_string(),
+                                  "box".to_string()).unwrap();
+
+        // The self-loop comes out once, though it's stored twice
+        assert_eq!(graph.to_dot(),
+                   "graph {\n    \"a\" [\"colour\"=\"red\", \"shape\"=\"box\"];\n    \"b\";\n    \"c\";\n    \"a\" -- \"b\";\n    \"c\" -- \"c\";\n}\n".to_string());
+    }
+
+    #[test]
+    fn to_dot_escapes_labels() {
+        let mut graph = Graph::new();
+        graph.add_edge(&s("dir\\"), &s("say \"hi\""));
+        let dot = graph.to_dot();
+        let dot = dot.as_slice();
+        assert!(dot.contains("    \"dir\\\\\";\n"));
+        assert!(dot.contains("    \"say \\\"hi\\\"\";\n"));
+        assert!(dot.contains("    \"dir\\\\\" -- \"say \\\"hi\\\"\";\n"));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut graph = Graph::with_name("mixed \"nodes\"".to_string());
+        graph.add_weighted_edge(&s("a"), &Node::Int(5), 2.5);
+        graph.add_edge(&Node::Int(5), &Node::Int(-3));
+        graph.add_node(s("5"));
+        graph.set_node_attr_value(&s("a"), "colour".to_string(),
+                                  "red".to_string()).unwrap();
+        let mut road = HashMap::new();
+        road.insert("road".to_string(), "A10".to_string());
+        graph.set_edge_attr(&Node::Int(5), &s("a"), road.clone()).unwrap();
+
+        let copy = Graph::from_json(graph.to_json().as_slice()).unwrap();
+        assert!(copy == graph);
+        assert_eq!(copy.name(), graph.name());
+        assert_eq!(copy.get_edge_attr(&s("a"), &Node::Int(5)), Ok(&road));
+        // Int(5) and Str("5") are kept apart by the type tag
+        assert!(copy.contains_node(&Node::Int(5)));
+        assert!(copy.contains_node(&s("5")));
+        assert_eq!(copy.degree(&s("5")), Ok(0));
+    }
+
+    #[test]
+    fn json_round_trip_of_non_finite_weights() {
+        let mut graph = Graph::new();
+        graph.add_weighted_edge(&Node::Int(0), &Node::Int(1), Float::nan());
+        graph.add_weighted_edge(&Node::Int(1), &Node::Int(2),
+                                Float::infinity());
+        graph.add_weighted_edge(&Node::Int(2), &Node::Int(3),
+                                Float::neg_infinity());
+
+        let json = graph.to_json();
+        assert!(json.as_slice().contains("[0, 1, \"NaN\", {}]"));
+        let copy = Graph::from_json(json.as_slice()).unwrap();
+        assert!(copy.edge_weight(&Node::Int(0), &Node::Int(1)).unwrap()
+                    .is_nan());
+        assert_eq!(copy.edge_weight(&Node::Int(1), &Node::Int(2)),
+                   Ok(Float::infinity()));
+        assert_eq!(copy.edge_weight(&Node::Int(3), &Node::Int(2)),
+                   Ok(Float::neg_infinity()));
+    }
+
+    #[test]
+    fn from_json_rejects_bad_input() {
+        assert!(Graph::from_json("{\"name\": \"x\"").is_err());
+        assert_eq!(Graph::from_json("{\"name\": \"x\", \"nodes\": [], \"edges\": [[0, 1]]}"),
+                   Err(GraphError::ParseError(0)));
+    }
+
+    #[test]
+    fn from_edge_list_skips_comments_and_blanks() {
+        let text = "# a comment\n\n1 2\n   \n  # indented comment\n2 3\n";
+        let graph = Graph::from_edge_list(text).unwrap();
+        assert!(graph == graph_of(&[(1, 2), (2, 3)]));
+    }
+
+    #[test]
+    fn from_edge_list_numeric_and_string_tokens() {
+        let graph = Graph::from_edge_list("1 a\n-4\tb1\n").unwrap();
+        assert!(graph.contains_edge(&Node::Int(1), &s("a")));
+        assert!(graph.contains_edge(&Node::Int(-4), &s("b1")));
+        assert!(!graph.contains_node(&s("1")));
+        assert_eq!(graph.node_count(), 4);
+    }
+
+    #[test]
+    fn from_edge_list_reports_malformed_line() {
+        assert_eq!(Graph::from_edge_list("1 2\n# fine\n1 2 3\n"),
+                   Err(GraphError::ParseError(3)));
+    }
+
+    #[test]
+    fn adjacency_matrix_of_triangle() {
+        let mut graph = graph_of(&[(0, 1), (1, 2), (2, 0)]);
+        graph.add_node(Node::Int(3));
+        assert_eq!(graph.to_adjacency_matrix(),
+                   vec![vec![0, 1, 1, 0],
+                        vec![1, 0, 1, 0],
+                        vec![1, 1, 0, 0],
+                        vec![0, 0, 0, 0]]);
+    }
+
+    #[test]
+    fn adjacency_matrix_diagonal_shows_self_loops() {
+        let mut graph = graph_of(&[(0, 1)]);
+        graph.allow_self_loops(true);
+        graph.add_edge(&Node::Int(1), &Node::Int(1));
+        assert_eq!(graph.to_adjacency_matrix(),
+                   vec![vec![0, 1], vec![1, 1]]);
+    }
+
+    #[test]
+    fn from_adjacency_matrix_reads_upper_triangle() {
+        // The lower triangle is ignored, even where it disagrees
+        let matrix = [vec![0, 1, 0],
+                      vec![0, 0, 1],
+                      vec![1, 1, 0]];
+        let graph = Graph::from_adjacency_matrix(&matrix, vec![s("a"), s("b"),
+                                                              s("c")]).unwrap();
+        assert_eq!(graph.edge_count(), 2);
+        assert!(graph.contains_edge(&s("b"), &s("a")));
+        assert!(graph.contains_edge(&s("b"), &s("c")));
+        assert!(!graph.contains_edge(&s("a"), &s("c")));
+        assert_eq!(graph.to_adjacency_matrix(),
+                   vec![vec![0, 1, 0], vec![1, 0, 1], vec![0, 1, 0]]);
+    }
+
+    #[test]
+    fn from_adjacency_matrix_dimension_checks() {
+        let not_square = [vec![0, 1], vec![1, 0, 0]];
+        assert_eq!(Graph::from_adjacency_matrix(&not_square,
+                                                vec![s("a"), s("b")]),
+                   Err(GraphError::DimensionMismatch));
+        let square = [vec![0, 1], vec![1, 0]];
+        assert_eq!(Graph::from_adjacency_matrix(&square, vec![s("a")]),
+                   Err(GraphError::DimensionMismatch));
+    }
+
+    #[test]
+    fn get_node_attr_reads_back_attributes() {
+        let mut graph = Graph::new();
+        graph.add_node(s("a"));
+        let mut attrs = HashMap::new();
+        attrs.insert("colour".to_string(), "red".to_string());
+        attrs.insert("size".to_string(), "3".to_string());
+        graph.set_node_attr(&s("a"), attrs.clone()).unwrap();
+
+        assert_eq!(graph.get_node_attr(&s("a")), Ok(&attrs));
+        assert_eq!(graph.get_node_attr_value(&s("a"), "colour"),
+                   Some(&"red".to_string()));
+        assert_eq!(graph.get_node_attr_value(&s("a"), "size"),
+                   Some(&"3".to_string()));
+        assert_eq!(graph.get_node_attr_value(&s("a"), "shape"), None);
+        assert_eq!(graph.get_node_attr_value(&s("z"), "colour"), None);
+        assert_eq!(graph.get_node_attr(&s("z")),
+                   Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn set_node_attr_value_keeps_other_keys() {
+        let mut graph = Graph::new();
+        graph.add_node(s("a"));
+        graph.set_node_attr_value(&s("a"), "colour".to_string(),
+                                  "red".to_string()).unwrap();
+        graph.set_node_attr_value(&s("a"), "size".to_string(),
+                                  "3".to_string()).unwrap();
+        graph.set_node_attr_value(&s("a"), "colour".to_string(),
+                                  "blue".to_string()).unwrap();
+        assert_eq!(graph.get_node_attr_value(&s("a"), "colour"),
+                   Some(&"blue".to_string()));
+        assert_eq!(graph.get_node_attr_value(&s("a"), "size"),
+                   Some(&"3".to_string()));
+        assert_eq!(graph.get_node_attr(&s("a")).unwrap().len(), 2);
+        assert_eq!(graph.set_node_attr_value(&s("z"), "k".to_string(),
+                                             "v".to_string()),
+                   Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn remove_node_attr_value_removes_one_key() {
+        let mut graph = Graph::new();
+        graph.add_node(s("a"));
+        graph.set_node_attr_value(&s("a"), "colour".to_string(),
+                                  "red".to_string()).unwrap();
+        graph.set_node_attr_value(&s("a"), "size".to_string(),
+                                  "3".to_string()).unwrap();
+        assert_eq!(graph.remove_node_attr_value(&s("a"), "colour"),
+                   Some("red".to_string()));
+        assert_eq!(graph.remove_node_attr_value(&s("a"), "colour"), None);
+        assert_eq!(graph.get_node_attr_value(&s("a"), "size"),
+                   Some(&"3".to_string()));
+        assert_eq!(graph.remove_node_attr_value(&s("z"), "size"), None);
+    }
+
+    #[test]
+    fn edge_attributes_go_with_the_edge() {
+        let mut graph = graph_of(&[(2, 1), (1, 3)]);
+        let mut attrs = HashMap::new();
+        attrs.insert("weight".to_string(), "heavy".to_string());
+        graph.set_edge_attr(&Node::Int(2), &Node::Int(1), attrs.clone())
+             .unwrap();
+
+        // Either order of the two nodes finds the same edge
+        assert_eq!(graph.get_edge_attr(&Node::Int(1), &Node::Int(2)),
+                   Ok(&attrs));
+        assert!(graph.get_edge_attr(&Node::Int(1), &Node::Int(3)).unwrap()
+                     .is_empty());
+
+        graph.remove_edge(&Node::Int(1), &Node::Int(2)).unwrap();
+        assert_eq!(graph.get_edge_attr(&Node::Int(1), &Node::Int(2)),
+                   Err(GraphError::EdgeNotFound));
+        assert_eq!(graph.edge_attr_list.len(), 1);
+
+        // A new edge between the same nodes starts out bare
+        graph.add_edge(&Node::Int(1), &Node::Int(2));
+        assert!(graph.get_edge_attr(&Node::Int(2), &Node::Int(1)).unwrap()
+                     .is_empty());
+    }
+
+    #[test]
+    fn edge_attributes_of_missing_edges() {
+        let mut graph = graph_of(&[(1, 2)]);
+        graph.add_node(Node::Int(3));
+        assert_eq!(graph.set_edge_attr(&Node::Int(1), &Node::Int(3),
+                                       HashMap::new()),
+                   Err(GraphError::EdgeNotFound));
+        assert_eq!(graph.get_edge_attr(&Node::Int(1), &Node::Int(9)),
+                   Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn nodes_iter_in_insertion_order() {
+        let mut graph = Graph::new();
+        graph.add_node(s("c"));
+        graph.add_edge(&s("a"), &s("c"));
+        graph.add_node(Node::Int(1));
+        let nodes: Vec<&Node> = graph.nodes_iter().collect();
+        assert_eq!(nodes, vec![&s("c"), &s("a"), &Node::Int(1)]);
+        assert_eq!(graph.nodes_iter().filter(|n| **n != s("a")).count(), 2);
+    }
+
+    #[test]
+    fn edges_iter_yields_each_edge_once() {
+        let graph = graph_of(&[(0, 1), (2, 1), (2, 3)]);
+        let edges: Vec<(&Node, &Node)> = graph.edges_iter().collect();
+        assert_eq!(edges, vec![(&Node::Int(0), &Node::Int(1)),
+                               (&Node::Int(1), &Node::Int(2)),
+                               (&Node::Int(2), &Node::Int(3))]);
+    }
+
+    #[test]
+    fn edges_iter_yields_self_loop_once() {
+        let mut graph = graph_of(&[(0, 1)]);
+        graph.allow_self_loops(true);
+        graph.add_edge(&Node::Int(1), &Node::Int(1));
+        assert_eq!(graph.edges_iter().count(), 2);
+    }
+
+    #[test]
+    fn contains_queries_with_missing_nodes() {
+        let graph = graph_of(&[(1, 2)]);
+        assert!(graph.contains_node(&Node::Int(1)));
+        assert!(!graph.contains_node(&Node::Int(9)));
+        assert!(graph.contains_edge(&Node::Int(2), &Node::Int(1)));
+        // No panic when either end isn't in the graph
+        assert!(!graph.contains_edge(&Node::Int(1), &Node::Int(9)));
+        assert!(!graph.contains_edge(&Node::Int(9), &Node::Int(1)));
+        assert!(!graph.contains_edge(&Node::Int(8), &Node::Int(9)));
+    }
+
+    #[test]
+    fn add_edge_between_new_nodes() {
+        // Used to panic in has_edge before either node was inserted
+        let mut graph = Graph::new();
+        graph.add_edge(&s("x"), &s("y"));
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 1);
+        assert!(graph.contains_edge(&s("y"), &s("x")));
+    }
+
+    #[test]
+    fn add_edge_mixes_existing_and_new_nodes() {
+        let mut graph = Graph::new();
+        graph.add_node(s("a"));
+        graph.add_node(s("b"));
+        graph.add_edge(&s("a"), &s("c"));
+        graph.add_edge(&s("d"), &s("b"));
+        graph.add_edge(&s("a"), &s("b"));
+
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.get_index(&s("c")), 2);
+        assert_eq!(graph.get_index(&s("d")), 3);
+        assert_eq!(graph.neighbors(&s("a")), Ok(vec![&s("c"), &s("b")]));
+        assert_eq!(graph.neighbors(&s("b")), Ok(vec![&s("d"), &s("a")]));
+        assert_eq!(graph.edge_count(), 3);
+    }
+
+    #[test]
+    fn graph_of_u64() {
+        let mut graph = Graph::<u64>::new();
+        graph.add_edge(&1, &2);
+        graph.add_edge(&2, &3);
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.shortest_path(&1, &3), Ok(Some(vec![&1, &2, &3])));
+        assert_eq!(graph.remove_node(&1), Ok(1));
+        assert!(graph.contains_edge(&3, &2));
+    }
+
+    #[deriving(Eq, PartialEq, Hash, Clone, Show)]
+    struct City {
+        name: String,
+        population: uint,
+    }
+
+    #[test]
+    fn graph_of_custom_struct() {
+        let paris = City { name: "Paris".to_string(), population: 2100000 };
+        let lyon = City { name: "Lyon".to_string(), population: 500000 };
+        let mut graph = Graph::new();
+        graph.add_weighted_edge(&paris, &lyon, 465.0);
+        assert_eq!(graph.edge_weight(&lyon, &paris), Ok(465.0));
+        assert_eq!(graph.neighbors(&paris), Ok(vec![&lyon]));
+
+        // Only equal payloads are the same node
+        let other_paris = City { name: "Paris".to_string(), population: 0 };
+        assert!(!graph.contains_node(&other_paris));
+        assert_eq!(graph.add_node(paris.clone()), 0);
+    }
+
+    #[test]
+    fn clone_is_independent_of_original() {
+        let mut graph = graph_of(&[(0, 1), (1, 2), (2, 0)]);
+        graph.set_name("triangle".to_string());
+        graph.set_node_attr_value(&Node::Int(0), "colour".to_string(),
+                                  "red".to_string()).unwrap();
+
+        let mut copy = graph.clone();
+        assert!(copy == graph);
+        assert_eq!(copy.name(), graph.name());
+
+        copy.remove_node(&Node::Int(0)).unwrap();
+        copy.set_node_attr_value(&Node::Int(1), "colour".to_string(),
+                                 "blue".to_string()).unwrap();
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        assert!(graph.contains_edge(&Node::Int(0), &Node::Int(2)));
+        assert_eq!(graph.get_node_attr_value(&Node::Int(0), "colour"),
+                   Some(&"red".to_string()));
+        assert_eq!(graph.get_node_attr_value(&Node::Int(1), "colour"), None);
+        assert_eq!(copy.node_count(), 2);
+    }
+
+    #[test]
+    fn graphs_built_in_different_orders_are_equal() {
+        let graph1 = graph_of(&[(0, 1), (1, 2), (2, 3)]);
+        let mut graph2 = Graph::new();
+        graph2.add_node(Node::Int(3));
+        graph2.add_edge(&Node::Int(2), &Node::Int(1));
+        graph2.add_edge(&Node::Int(3), &Node::Int(2));
+        graph2.add_edge(&Node::Int(1), &Node::Int(0));
+        assert!(graph1 == graph2);
+        assert!(graph2 == graph1);
+    }
+
+    #[test]
+    fn extra_edge_or_attribute_makes_graphs_unequal() {
+        let graph1 = graph_of(&[(0, 1), (1, 2)]);
+        let mut graph2 = graph1.clone();
+        graph2.add_edge(&Node::Int(0), &Node::Int(2));
+        assert!(graph1 != graph2);
+
+        let mut graph3 = graph1.clone();
+        graph3.set_node_attr_value(&Node::Int(2), "colour".to_string(),
+                                   "red".to_string()).unwrap();
+        assert!(graph1 != graph3);
+
+        let mut graph4 = graph1.clone();
+        graph4.add_weighted_edge(&Node::Int(0), &Node::Int(1), 2.0);
+        assert!(graph1 != graph4);
+    }
+
+    #[test]
+    fn graph_with_nan_weight_equals_its_clone() {
+        let mut graph = graph_of(&[(0, 1)]);
+        graph.add_weighted_edge(&Node::Int(1), &Node::Int(2), Float::nan());
+        assert!(graph == graph.clone());
+
+        let mut other = graph.clone();
+        other.add_weighted_edge(&Node::Int(1), &Node::Int(2), 1.0);
+        assert!(graph != other);
+    }
+
+    #[test]
+    fn subgraph_of_two_triangle_nodes() {
+        let mut graph = graph_of(&[(0, 1), (1, 2), (2, 0)]);
+        graph.set_node_attr_value(&Node::Int(2), "colour".to_string(),
+                                  "red".to_string()).unwrap();
+        let sub = graph.subgraph(&[Node::Int(2), Node::Int(1)]).unwrap();
+        assert_eq!(sub.node_count(), 2);
+        assert_eq!(sub.edge_count(), 1);
+        assert!(sub.contains_edge(&Node::Int(1), &Node::Int(2)));
+        assert!(!sub.contains_node(&Node::Int(0)));
+        assert_eq!(sub.get_node_attr_value(&Node::Int(2), "colour"),
+                   Some(&"red".to_string()));
+    }
+
+    #[test]
+    fn subgraph_with_unknown_node() {
+        let graph = graph_of(&[(0, 1)]);
+        assert_eq!(graph.subgraph(&[Node::Int(0), Node::Int(7)]),
+                   Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn union_of_triangles_sharing_a_node() {
+        let mut graph1 = graph_of(&[(0, 1), (1, 2), (2, 0)]);
+        let mut graph2 = graph_of(&[(2, 3), (3, 4), (4, 2)]);
+        graph1.set_node_attr_value(&Node::Int(2), "colour".to_string(),
+                                   "red".to_string()).unwrap();
+        graph2.set_node_attr_value(&Node::Int(2), "colour".to_string(),
+                                   "blue".to_string()).unwrap();
+        graph2.set_node_attr_value(&Node::Int(2), "size".to_string(),
+                                   "3".to_string()).unwrap();
+
+        let union = graph1.union(&graph2);
+        assert_eq!(union.node_count(), 5);
+        assert_eq!(union.edge_count(), 6);
+        assert_eq!(union.degree(&Node::Int(2)), Ok(4));
+        for &(a, b) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4),
+                        (4, 2)].iter() {
+            assert!(union.contains_edge(&Node::Int(a), &Node::Int(b)));
+        }
+        // self's attributes win, the rest are merged in
+        assert_eq!(union.get_node_attr_value(&Node::Int(2), "colour"),
+                   Some(&"red".to_string()));
+        assert_eq!(union.get_node_attr_value(&Node::Int(2), "size"),
+                   Some(&"3".to_string()));
+        // Neither input is changed
+        assert_eq!(graph1.node_count(), 3);
+        assert_eq!(graph2.node_count(), 3);
+    }
+
+    #[test]
+    fn intersection_keeps_shared_path() {
+        // Both contain the path 1-2-3; each has extras of its own
+        let graph1 = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let graph2 = graph_of(&[(1, 2), (2, 3), (3, 4), (1, 3)]);
+        let common = graph1.intersection(&graph2);
+        assert_eq!(common.node_count(), 3);
+        assert_eq!(common.edge_count(), 2);
+        assert!(common == graph_of(&[(1, 2), (2, 3)]));
+    }
+
+    #[test]
+    fn complement_of_three_node_path() {
+        let graph = graph_of(&[(0, 1), (1, 2)]);
+        let complement = graph.complement();
+        assert_eq!(complement.node_count(), 3);
+        assert_eq!(complement.edge_count(), 1);
+        assert!(complement.contains_edge(&Node::Int(0), &Node::Int(2)));
+        assert!(complement.self_loops().is_empty());
+    }
+
+    #[test]
+    fn self_loops_blocked_by_default() {
+        let mut graph = Graph::new();
+        graph.add_edge(&s("a"), &s("a"));
+        graph.add_weighted_edge(&s("b"), &s("b"), 2.0);
+        // The nodes are added, the loops aren't
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 0);
+        assert!(graph.self_loops().is_empty());
+        assert_eq!(graph.has_self_loop(&s("a")), Ok(false));
+        assert_eq!(graph.degree(&s("a")), Ok(0));
+    }
+
+    #[test]
+    fn self_loops_when_allowed() {
+        let mut graph = graph_of(&[(0, 1)]);
+        graph.allow_self_loops(true);
+        graph.add_edge(&Node::Int(1), &Node::Int(1));
+        graph.add_edge(&Node::Int(2), &Node::Int(2));
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.self_loops(), vec![&Node::Int(1), &Node::Int(2)]);
+        assert_eq!(graph.has_self_loop(&Node::Int(0)), Ok(false));
+        assert_eq!(graph.has_self_loop(&Node::Int(9)),
+                   Err(GraphError::NodeNotFound));
+
+        // Turning the option off again keeps the loops already there
+        graph.allow_self_loops(false);
+        graph.add_edge(&Node::Int(0), &Node::Int(0));
+        assert_eq!(graph.self_loops(), vec![&Node::Int(1), &Node::Int(2)]);
+    }
+
+    #[test]
+    fn subgraph_keeps_loops_with_option_off() {
+        let mut graph = graph_of(&[(0, 1)]);
+        graph.allow_self_loops(true);
+        graph.add_edge(&Node::Int(1), &Node::Int(1));
+        graph.allow_self_loops(false);
+
+        let sub = graph.subgraph(&[Node::Int(0), Node::Int(1)]).unwrap();
+        assert!(sub == graph);
+        assert_eq!(sub.self_loops(), vec![&Node::Int(1)]);
+        assert!(!sub.allow_self_loops);
+        // One attribute map per edge, none left over
+        assert_eq!(sub.edge_attr_list.len(), sub.edge_count());
+    }
+
+    #[test]
+    fn importers_keep_loops_without_allowing_more() {
+        let graph = Graph::from_edge_list("a a\na b\n").unwrap();
+        assert_eq!(graph.self_loops(), vec![&s("a")]);
+        assert!(!graph.allow_self_loops);
+
+        let json = "{\"name\": \"\", \"nodes\": [{\"type\": \"int\", \"value\": 1}], \"edges\": [[0, 0]]}";
+        let graph = Graph::from_json(json).unwrap();
+        assert_eq!(graph.self_loops(), vec![&Node::Int(1)]);
+        assert!(!graph.allow_self_loops);
+
+        let matrix = [vec![1, 1], vec![1, 0]];
+        let mut graph = Graph::from_adjacency_matrix(&matrix,
+                                                     vec![s("a"), s("b")])
+                              .unwrap();
+        assert_eq!(graph.self_loops(), vec![&s("a")]);
+        assert!(!graph.allow_self_loops);
+        graph.add_edge(&s("b"), &s("b"));
+        assert_eq!(graph.self_loops(), vec![&s("a")]);
+    }
+
+    #[test]
+    fn re_adding_a_node_is_idempotent() {
+        let mut graph = Graph::new();
+        graph.add_node(s("w"));
+        let first = graph.add_node(s("x"));
+        let second = graph.add_node(s("x"));
+        let third = graph.add_node(s("x"));
+        assert_eq!(first, 1);
+        assert_eq!(second, first);
+        assert_eq!(third, first);
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.node_index.len(), 2);
+        assert_eq!(graph.adj_list.len(), 2);
+    }
+
+    #[test]
+    fn degree_sequence_of_star() {
+        let graph = graph_of(&[(0, 1), (0, 2), (0, 3), (0, 4)]);
+        assert_eq!(graph.degree_sequence(), vec![4, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn degree_histogram_of_star() {
+        let mut graph = graph_of(&[(0, 1), (0, 2), (0, 3), (0, 4)]);
+        graph.add_node(Node::Int(5));
+        let histogram = graph.degree_histogram();
+        assert_eq!(histogram.len(), 3);
+        assert_eq!(histogram[4], 1);
+        assert_eq!(histogram[1], 4);
+        assert_eq!(histogram[0], 1);
+    }
+
+    #[test]
+    fn path_is_connected() {
+        assert!(graph_of(&[(0, 1), (1, 2), (2, 3)]).is_connected());
+    }
+
+    #[test]
+    fn two_components_are_not_connected() {
+        assert!(!graph_of(&[(0, 1), (2, 3)]).is_connected());
+    }
+
+    #[test]
+    fn single_and_empty_graphs_are_connected() {
+        let mut graph = Graph::new();
+        assert!(graph.is_connected());
+        graph.add_node(Node::Int(0));
+        assert!(graph.is_connected());
+        graph.add_node(Node::Int(1));
+        assert!(!graph.is_connected());
+    }
+
+    #[test]
+    fn mst_of_square_with_diagonal() {
+        let mut graph = Graph::new();
+        graph.add_weighted_edge(&s("a"), &s("b"), 1.0);
+        graph.add_weighted_edge(&s("b"), &s("c"), 2.0);
+        graph.add_weighted_edge(&s("c"), &s("d"), 5.0);
+        graph.add_weighted_edge(&s("d"), &s("a"), 4.0);
+        graph.add_weighted_edge(&s("a"), &s("c"), 3.0);
+
+        // a-c would close a cycle with a-b-c, so d-a is the third edge
+        let tree = graph.minimum_spanning_tree().unwrap();
+        assert_eq!(tree.node_count(), 4);
+        assert_eq!(tree.edge_count(), 3);
+        assert_eq!(tree.edge_weight(&s("a"), &s("b")), Ok(1.0));
+        assert_eq!(tree.edge_weight(&s("b"), &s("c")), Ok(2.0));
+        assert_eq!(tree.edge_weight(&s("a"), &s("d")), Ok(4.0));
+        assert!(tree.is_tree());
+    }
+
+    #[test]
+    fn mst_of_disconnected_graph_is_forest() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 0), (3, 4)]);
+        let forest = graph.minimum_spanning_tree().unwrap();
+        assert_eq!(forest.node_count(), 5);
+        assert_eq!(forest.edge_count(), 3);
+        assert!(forest.contains_edge(&Node::Int(3), &Node::Int(4)));
+        assert!(!forest.has_cycle());
+    }
+
+    #[test]
+    fn clustering_of_triangle() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 0)]);
+        for i in range(0i, 3) {
+            assert_eq!(graph.clustering_coefficient(&Node::Int(i)), Ok(1.0));
+        }
+        assert_eq!(graph.average_clustering(), 1.0);
+    }
+
+    #[test]
+    fn clustering_of_star() {
+        let mut graph = graph_of(&[(0, 1), (0, 2), (0, 3)]);
+        assert_eq!(graph.clustering_coefficient(&Node::Int(1)), Ok(0.0));
+        assert_eq!(graph.clustering_coefficient(&Node::Int(0)), Ok(0.0));
+
+        // One of the three pairs of the hub's neighbors is joined
+        graph.add_edge(&Node::Int(1), &Node::Int(2));
+        assert_eq!(graph.clustering_coefficient(&Node::Int(0)),
+                   Ok(1.0 / 3.0));
+        assert_eq!(graph.clustering_coefficient(&Node::Int(3)), Ok(0.0));
+        assert_eq!(graph.clustering_coefficient(&Node::Int(9)),
+                   Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn distances_along_path() {
+        let mut graph = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        graph.add_node(Node::Int(5));
+        let dist = graph.distances_from(&Node::Int(0)).unwrap();
+        // Node i is at index i; each one is a hop further than the last
+        assert_eq!(dist.len(), 5);
+        for i in range(0u, 5) {
+            assert_eq!(dist[i], i);
+        }
+        assert!(!dist.contains_key(&5));
+        assert_eq!(graph.distances_from(&Node::Int(9)),
+                   Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn diameter_and_radius_of_path() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(graph.diameter(), Some(3));
+        assert_eq!(graph.radius(), Some(2));
+    }
+
+    #[test]
+    fn diameter_and_radius_of_star() {
+        let graph = graph_of(&[(0, 1), (0, 2), (0, 3), (0, 4)]);
+        assert_eq!(graph.diameter(), Some(2));
+        assert_eq!(graph.radius(), Some(1));
+    }
+
+    #[test]
+    fn diameter_of_disconnected_graph() {
+        let graph = graph_of(&[(0, 1), (2, 3)]);
+        assert_eq!(graph.diameter(), None);
+        assert_eq!(graph.radius(), None);
+        assert_eq!(Graph::<Node>::new().diameter(), None);
+    }
+
+    #[test]
+    fn graph_name() {
+        let mut graph = Graph::<Node>::with_name("roads".to_string());
+        assert_eq!(graph.name(), &"roads".to_string());
+        graph.set_name("rail".to_string());
+        assert_eq!(graph.name(), &"rail".to_string());
+        assert_eq!(Graph::<Node>::new().name(), &String::new());
+
+        assert!(graph.to_dot().as_slice().starts_with("graph \"rail\" {\n"));
+        assert!(graph.to_json().as_slice()
+                     .starts_with("{\"name\": \"rail\", "));
+    }
+
+    #[test]
+    fn clear_edges_keeps_nodes() {
+        let mut graph = graph_of(&[(0, 1), (1, 2), (2, 0)]);
+        graph.set_node_attr_value(&Node::Int(1), "colour".to_string(),
+                                  "red".to_string()).unwrap();
+        graph.clear_edges();
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(graph.node_count(), 3);
+        assert!(graph.edge_attr_list.is_empty());
+        assert_eq!(graph.get_node_attr_value(&Node::Int(1), "colour"),
+                   Some(&"red".to_string()));
+
+        // Edges can be added again afterwards
+        graph.add_edge(&Node::Int(0), &Node::Int(1));
+        assert_eq!(graph.edge_count(), 1);
+    }
+
+    #[test]
+    fn clear_resets_everything_but_the_name() {
+        let mut graph = graph_of(&[(0, 1), (1, 2)]);
+        graph.set_name("reused".to_string());
+        graph.clear();
+        assert_eq!(graph.node_count(), 0);
+        assert_eq!(graph.edge_count(), 0);
+        assert!(!graph.contains_node(&Node::Int(0)));
+        assert_eq!(graph.name(), &"reused".to_string());
+
+        assert_eq!(graph.add_node(Node::Int(7)), 0);
+        assert_eq!(graph.add_node(Node::Int(0)), 1);
+        assert!(graph.attr_list[0].is_empty());
+    }
+
+    #[test]
+    fn even_cycle_is_bipartite() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert!(graph.is_bipartite());
+        let color = graph.bipartite_coloring().unwrap();
+        assert_eq!(color.len(), 4);
+        for &(a, b, _) in graph.weighted_edges().iter() {
+            assert!(color[a] != color[b]);
+        }
+    }
+
+    #[test]
+    fn odd_cycle_is_not_bipartite() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 0)]);
+        assert!(!graph.is_bipartite());
+        assert_eq!(graph.bipartite_coloring(),
+                   Err(GraphError::NotBipartite));
+    }
+
+    #[test]
+    fn every_component_is_colored() {
+        let mut graph = graph_of(&[(0, 1), (2, 3), (3, 4)]);
+        graph.add_node(Node::Int(5));
+        let color = graph.bipartite_coloring().unwrap();
+        assert_eq!(color.len(), 6);
+        assert!(color[0] != color[1]);
+        assert!(color[2] != color[3]);
+        assert_eq!(color[2], color[4]);
+    }
+
+    #[test]
+    fn contract_edge_of_path() {
+        // 0 - 1 - 2 - 3, contract 1-2 into 1
+        let mut graph = graph_of(&[(0, 1), (1, 2), (2, 3)]);
+        graph.set_node_attr_value(&Node::Int(1), "colour".to_string(),
+                                  "red".to_string()).unwrap();
+        graph.set_node_attr_value(&Node::Int(2), "colour".to_string(),
+                                  "blue".to_string()).unwrap();
+        graph.set_node_attr_value(&Node::Int(2), "size".to_string(),
+                                  "3".to_string()).unwrap();
+        assert_eq!(graph.contract_edge(&Node::Int(1), &Node::Int(2)), Ok(()));
+
+        assert!(!graph.contains_node(&Node::Int(2)));
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.neighbors(&Node::Int(1)),
+                   Ok(vec![&Node::Int(0), &Node::Int(3)]));
+        assert!(graph.self_loops().is_empty());
+        assert_eq!(graph.get_node_attr_value(&Node::Int(1), "colour"),
+                   Some(&"red".to_string()));
+        assert_eq!(graph.get_node_attr_value(&Node::Int(1), "size"),
+                   Some(&"3".to_string()));
+    }
+
+    #[test]
+    fn contract_edge_collapses_shared_neighbors() {
+        // In a triangle both ends already share the third node
+        let mut graph = graph_of(&[(0, 1), (1, 2), (2, 0)]);
+        graph.contract_edge(&Node::Int(0), &Node::Int(1)).unwrap();
+        assert_eq!(graph.edge_count(), 1);
+        assert!(!graph.has_multi_edges());
+        assert_eq!(graph.contract_edge(&Node::Int(0), &Node::Int(9)),
+                   Err(GraphError::NodeNotFound));
+        graph.add_node(Node::Int(5));
+        assert_eq!(graph.contract_edge(&Node::Int(0), &Node::Int(5)),
+                   Err(GraphError::EdgeNotFound));
+    }
+
+    #[test]
+    fn articulation_points_of_barbell() {
+        // Triangles 0-1-2 and 3-4-5, joined by the bridge 2-3
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5),
+                               (5, 3)]);
+        assert_eq!(graph.articulation_points(),
+                   vec![&Node::Int(2), &Node::Int(3)]);
+    }
+
+    #[test]
+    fn articulation_points_of_path_and_cycle() {
+        let path = graph_of(&[(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(path.articulation_points(),
+                   vec![&Node::Int(1), &Node::Int(2)]);
+        let cycle = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert!(cycle.articulation_points().is_empty());
+    }
+
+    #[test]
+    fn bridge_between_two_triangles() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5),
+                               (5, 3)]);
+        assert_eq!(graph.bridges(), vec![(&Node::Int(2), &Node::Int(3))]);
+    }
+
+    #[test]
+    fn every_edge_of_a_tree_is_a_bridge() {
+        let graph = graph_of(&[(0, 1), (1, 2), (1, 3)]);
+        assert_eq!(graph.bridges().len(), 3);
+        assert!(graph_of(&[(0, 1), (1, 2), (2, 0)]).bridges().is_empty());
+    }
+
+    #[test]
+    fn to_graphml_is_well_formed() {
+        let mut graph = graph_of(&[(0, 1), (1, 2)]);
+        graph.add_node(Node::Int(3));
+        graph.set_node_attr_value(&Node::Int(0), "colour".to_string(),
+                                  "<red> & \"blue\"".to_string()).unwrap();
+
+        let text = graph.to_graphml();
+        let root = xml::parse(text.as_slice()).unwrap();
+        assert_eq!(root.name, "graphml".to_string());
+        let graph_elem = root.children.iter()
+                             .find(|e| e.name.as_slice() == "graph").unwrap();
+        let count = |name: &str| {
+            graph_elem.children.iter()
+                      .filter(|e| e.name.as_slice() == name).count()
+        };
+        assert_eq!(count("node"), 4);
+        assert_eq!(count("edge"), 2);
+        assert!(text.as_slice().contains("<node id=\"n3\">"));
+        assert!(text.as_slice()
+                    .contains("<edge source=\"n1\" target=\"n2\">"));
+    }
+
+    #[test]
+    fn graphml_round_trip() {
+        let mut graph = Graph::with_name("cities".to_string());
+        graph.add_weighted_edge(&s("Paris"), &Node::Int(75), 2.5);
+        graph.add_edge(&Node::Int(75), &s("Orléans"));
+        graph.add_node(s("75"));
+        graph.allow_self_loops(true);
+        graph.add_edge(&s("75"), &s("75"));
+        graph.allow_self_loops(false);
+        graph.set_node_attr_value(&s("Paris"), "note".to_string(),
+                                  "<capital> & \"big\"".to_string()).unwrap();
+        let mut attrs = HashMap::new();
+        attrs.insert("road".to_string(), "A10".to_string());
+        graph.set_edge_attr(&s("Paris"), &Node::Int(75), attrs).unwrap();
+
+        let copy = Graph::from_graphml(graph.to_graphml().as_slice()).unwrap();
+        assert!(copy == graph);
+        assert_eq!(copy.name(), graph.name());
+        assert_eq!(copy.self_loops(), vec![&s("75")]);
+        assert!(!copy.allow_self_loops);
+    }
+
+    #[test]
+    fn from_graphml_skips_unknown_elements() {
+        // Element names outside ASCII used to panic the parser
+        let text = "<?xml version=\"1.0\"?>\n\
+                    <graphml>\n\
+                      <donnée à=\"1\"/>\n\
+                      <graph id=\"g\">\n\
+                        <desc>anything</desc>\n\
+                        <node id=\"à\"/>\n\
+                        <node id=\"b\"/>\n\
+                        <edge source=\"à\" target=\"b\"/>\n\
+                      </graph>\n\
+                    </graphml>\n";
+        let graph = Graph::from_graphml(text).unwrap();
+        assert_eq!(graph.name(), &"g".to_string());
+        assert_eq!(graph.edge_weight(&s("à"), &s("b")), Ok(1.0));
+        assert_eq!(graph.node_count(), 2);
+    }
+
+    #[test]
+    fn from_graphml_reports_malformed_xml() {
+        let text = "<graphml>\n  <graph>\n    <node id=\"a\">\n  </graph>\n</graphml>\n";
+        assert_eq!(Graph::from_graphml(text), Err(GraphError::ParseError(4)));
+        assert!(Graph::from_graphml("<other/>").is_err());
+    }
+
+    #[test]
+    fn relabel_nodes_to_upper_case() {
+        let mut graph = Graph::new();
+        graph.add_edge(&s("a"), &s("b"));
+        graph.add_edge(&s("b"), &Node::Int(3));
+        graph.set_node_attr_value(&s("a"), "colour".to_string(),
+                                  "red".to_string()).unwrap();
+        let result = graph.relabel_nodes(|node: &Node| {
+            match *node {
+                Node::Str(ref label) => {
+                    Node::Str(label.as_slice().to_ascii_upper())
+                }
+                Node::Int(v) => Node::Int(v),
+            }
+        });
+        assert_eq!(result, Ok(()));
+        assert!(!graph.contains_node(&s("a")));
+        assert!(graph.contains_edge(&s("A"), &s("B")));
+        assert!(graph.contains_edge(&s("B"), &Node::Int(3)));
+        assert_eq!(graph.get_node_attr_value(&s("A"), "colour"),
+                   Some(&"red".to_string()));
+        assert_eq!(graph.get_index(&s("B")), 1);
+    }
+
+    #[test]
+    fn relabel_nodes_collision_changes_nothing() {
+        let mut graph = graph_of(&[(1, 2), (2, 3)]);
+        let before = graph.clone();
+        assert_eq!(graph.relabel_nodes(|node: &Node| {
+                       match *node {
+                           Node::Int(v) => Node::Int(v / 2),
+                           Node::Str(ref label) => Node::Str(label.clone()),
+                       }
+                   }),
+                   Err(GraphError::LabelCollision));
+        assert!(graph == before);
+        assert!(graph.contains_node(&Node::Int(3)));
+        assert!(!graph.contains_node(&Node::Int(0)));
+    }
+
+    #[test]
+    fn nodes_by_degree_puts_hub_first() {
+        // The hub is added last, and the leaves tie with each other
+        let graph = graph_of(&[(1, 0), (2, 0), (3, 0), (3, 4)]);
+        assert_eq!(graph.nodes_by_degree(),
+                   vec![(&Node::Int(0), 3), (&Node::Int(3), 2),
+                        (&Node::Int(1), 1), (&Node::Int(2), 1),
+                        (&Node::Int(4), 1)]);
+    }
+
+    #[test]
+    fn greedy_coloring_of_k4() {
+        let graph = graph_of(&[(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+        let color = graph.greedy_coloring();
+        assert_eq!(color.len(), 4);
+        for &(a, b, _) in graph.weighted_edges().iter() {
+            assert!(color[a] != color[b]);
+        }
+        assert_eq!(graph.chromatic_number_upper_bound(), 4);
+    }
+
+    #[test]
+    fn greedy_coloring_of_even_cycle_uses_two_colors() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert_eq!(graph.chromatic_number_upper_bound(), 2);
+        assert_eq!(Graph::<Node>::new().chromatic_number_upper_bound(), 0);
+    }
+
+    #[test]
+    fn node_at_checks_bounds() {
+        let graph = graph_of(&[(4, 7)]);
+        assert_eq!(graph.node_at(0), Some(&Node::Int(4)));
+        assert_eq!(graph.node_at(1), Some(&Node::Int(7)));
+        assert_eq!(graph.node_at(2), None);
+        assert_eq!(Graph::<Node>::new().node_at(0), None);
+    }
+
+    #[test]
+    fn set_node_attr_on_missing_node() {
+        let mut graph = graph_of(&[(0, 1)]);
+        let mut attrs = HashMap::new();
+        attrs.insert("colour".to_string(), "red".to_string());
+        assert_eq!(graph.set_node_attr(&Node::Int(5), attrs.clone()),
+                   Err(GraphError::NodeNotFound));
+        assert!(!graph.contains_node(&Node::Int(5)));
+        assert_eq!(graph.set_node_attr(&Node::Int(1), attrs), Ok(()));
+    }
+
+    #[test]
+    fn add_edges_multiple_deduplicates() {
+        let mut graph = Graph::new();
+        graph.add_node(s("a"));
+        graph.add_edges_multiple(vec![(s("a"), s("b")), (s("b"), s("c")),
+                                      (s("c"), s("d")), (s("b"), s("a")),
+                                      (s("d"), s("e"))]);
+        assert_eq!(graph.edge_count(), 4);
+        assert_eq!(graph.node_count(), 5);
+        assert!(!graph.has_multi_edges());
+    }
+
+    #[test]
+    fn add_edges_multiple_keeps_existing_weights() {
+        let mut graph = Graph::new();
+        graph.add_weighted_edge(&s("a"), &s("b"), 3.0);
+        graph.add_edges_multiple(vec![(s("b"), s("a")), (s("a"), s("c"))]);
+        assert_eq!(graph.edge_weight(&s("a"), &s("b")), Ok(3.0));
+        assert_eq!(graph.edge_weight(&s("a"), &s("c")), Ok(1.0));
+    }
+
+    #[test]
+    fn is_reachable_along_path() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        assert_eq!(graph.is_reachable(&Node::Int(0), &Node::Int(4)), Ok(true));
+        assert_eq!(graph.is_reachable(&Node::Int(3), &Node::Int(1)), Ok(true));
+        assert_eq!(graph.is_reachable(&Node::Int(2), &Node::Int(2)), Ok(true));
+    }
+
+    #[test]
+    fn is_reachable_between_components() {
+        let graph = graph_of(&[(0, 1), (2, 3)]);
+        assert_eq!(graph.is_reachable(&Node::Int(0), &Node::Int(3)),
+                   Ok(false));
+        assert_eq!(graph.is_reachable(&Node::Int(0), &Node::Int(9)),
+                   Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn all_simple_paths_of_diamond() {
+        let graph = graph_of(&[(0, 1), (0, 2), (1, 3), (2, 3)]);
+        let paths = graph.all_simple_paths(&Node::Int(0), &Node::Int(3),
+                                           None).unwrap();
+        assert_eq!(paths, vec![vec![&Node::Int(0), &Node::Int(1),
+                                    &Node::Int(3)],
+                               vec![&Node::Int(0), &Node::Int(2),
+                                    &Node::Int(3)]]);
+        assert_eq!(graph.all_simple_paths(&Node::Int(0), &Node::Int(3),
+                                          Some(2)).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn all_simple_paths_with_length_cap() {
+        let graph = graph_of(&[(0, 1), (0, 2), (1, 3), (2, 3)]);
+        assert!(graph.all_simple_paths(&Node::Int(0), &Node::Int(3), Some(1))
+                     .unwrap().is_empty());
+        assert_eq!(graph.all_simple_paths(&Node::Int(0), &Node::Int(9), None),
+                   Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn k_core_strips_pendants() {
+        // K4 on 0..3, with 4 and 5 hanging off it and 6 hanging off 5
+        let graph = graph_of(&[(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3),
+                               (0, 4), (1, 5), (5, 6)]);
+        let core = graph.k_core(2);
+        assert!(core == graph_of(&[(0, 1), (0, 2), (0, 3), (1, 2), (1, 3),
+                                   (2, 3)]));
+        assert_eq!(graph.k_core(3).node_count(), 4);
+        assert_eq!(graph.k_core(4).node_count(), 0);
+        assert_eq!(graph.k_core(0).node_count(), 7);
+    }
+
+    #[test]
+    fn square_has_eulerian_circuit() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert!(graph.has_eulerian_circuit());
+        assert!(graph.has_eulerian_path());
+    }
+
+    #[test]
+    fn three_node_path_has_only_eulerian_path() {
+        let mut graph = graph_of(&[(0, 1), (1, 2)]);
+        assert!(!graph.has_eulerian_circuit());
+        assert!(graph.has_eulerian_path());
+
+        // An isolated node changes nothing; a second component does
+        graph.add_node(Node::Int(9));
+        assert!(graph.has_eulerian_path());
+        graph.add_edge(&Node::Int(7), &Node::Int(8));
+        assert!(!graph.has_eulerian_path());
+    }
+
+    #[test]
+    fn triangles_of_k4() {
+        let graph = graph_of(&[(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+        assert_eq!(graph.count_triangles(), 4);
+    }
+
+    #[test]
+    fn triangles_of_triangle_and_tree() {
+        assert_eq!(graph_of(&[(0, 1), (1, 2), (2, 0)]).count_triangles(), 1);
+        assert_eq!(graph_of(&[(0, 1), (1, 2), (1, 3)]).count_triangles(), 0);
+    }
+
+    #[test]
+    fn remove_node_at_middle_of_path() {
+        let mut graph = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        assert_eq!(graph.remove_node_at(2), Ok(Node::Int(2)));
+
+        // 4 moved into index 2; its edge to 3 comes with it
+        assert_eq!(graph.node_at(2), Some(&Node::Int(4)));
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.neighbors(&Node::Int(1)), Ok(vec![&Node::Int(0)]));
+        assert_eq!(graph.neighbors(&Node::Int(3)), Ok(vec![&Node::Int(4)]));
+        assert_eq!(graph.neighbors(&Node::Int(4)), Ok(vec![&Node::Int(3)]));
+        assert_eq!(graph.remove_node_at(4), Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn remove_node_reindexes_four_node_path() {
+        // 0 - 1 - 2 - 3, remove index 1; swap_remove moves 3 into its place
+        let mut graph = Graph::new();
+        graph.add_weighted_edge(&Node::Int(0), &Node::Int(1), 1.0);
+        graph.add_weighted_edge(&Node::Int(1), &Node::Int(2), 2.0);
+        graph.add_weighted_edge(&Node::Int(2), &Node::Int(3), 3.0);
+        let mut attrs = HashMap::new();
+        attrs.insert("name".to_string(), "last".to_string());
+        graph.set_edge_attr(&Node::Int(2), &Node::Int(3), attrs.clone())
+             .unwrap();
+        assert_eq!(graph.remove_node_at(1), Ok(Node::Int(1)));
+
+        assert_eq!(graph.nodes, vec![Node::Int(0), Node::Int(3), Node::Int(2)]);
+        assert!(graph.adj_list[0].is_empty());
+        assert_eq!(graph.adj_list[1], vec![(2, 3.0)]);
+        assert_eq!(graph.adj_list[2], vec![(1, 3.0)]);
+        assert_eq!(graph.adj_list.len(), 3);
+        assert_eq!(graph.get_edge_attr(&Node::Int(3), &Node::Int(2)),
+                   Ok(&attrs));
+        assert_eq!(graph.edge_attr_list.len(), 1);
+    }
+
+    #[test]
+    fn edge_list_round_trip() {
+        let text = "# roads\na b\nb 3\n3 3\n\nc\n";
+        let graph = Graph::from_edge_list(text).unwrap();
+        let exported = graph.to_edge_list();
+        assert_eq!(exported, "c\na b\nb 3\n3 3\n".to_string());
+        let again = Graph::from_edge_list(exported.as_slice()).unwrap();
+        assert!(again == graph);
+        assert_eq!(again.to_edge_list(), exported);
+    }
+
+    #[test]
+    fn edge_list_quotes_ambiguous_labels() {
+        let mut graph = Graph::new();
+        graph.add_edge(&s("42"), &s("#x"));
+        graph.add_edge(&s("1"), &Node::Int(1));
+        graph.add_edge(&s("two words"), &s("say \"hi\""));
+        graph.add_node(s(""));
+
+        let exported = graph.to_edge_list();
+        assert!(exported.as_slice().contains("\"42\" \"#x\"\n"));
+        assert!(exported.as_slice().contains("\"1\" 1\n"));
+        assert!(exported.as_slice()
+                        .contains("\"two words\" \"say \"\"hi\"\"\"\n"));
+        let again = Graph::from_edge_list(exported.as_slice()).unwrap();
+        assert!(again == graph);
+        assert!(again.contains_node(&s("")));
+        assert!(again.contains_edge(&s("1"), &Node::Int(1)));
+    }
+
+    #[test]
+    fn edge_list_rejects_bad_quotes() {
+        assert_eq!(Graph::from_edge_list("a b\n\"open b\n"),
+                   Err(GraphError::ParseError(2)));
+        assert_eq!(Graph::from_edge_list("\"a\"b c\n"),
+                   Err(GraphError::ParseError(1)));
+    }
+
+    #[test]
+    fn with_capacity_is_only_a_hint() {
+        let mut graph = Graph::with_capacity(10, 10);
+        for i in range(0i, 1000) {
+            graph.add_edge(&Node::Int(i), &Node::Int(i + 1));
+        }
+        assert_eq!(graph.node_count(), 1001);
+        assert_eq!(graph.edge_count(), 1000);
+        assert!(graph.is_tree());
+        assert!(graph.name().is_empty());
+        assert!(graph.contains_edge(&Node::Int(999), &Node::Int(1000)));
+    }
+
+    #[test]
+    fn density_of_triangle_and_path() {
+        assert_eq!(graph_of(&[(0, 1), (1, 2), (2, 0)]).density(), 1.0);
+        // 3 of the 6 possible edges
+        assert_eq!(graph_of(&[(0, 1), (1, 2), (2, 3)]).density(), 0.5);
+    }
+
+    #[test]
+    fn density_of_tiny_graphs() {
+        let mut graph = Graph::new();
+        assert_eq!(graph.density(), 0.0);
+        graph.add_node(Node::Int(0));
+        assert_eq!(graph.density(), 0.0);
+    }
+
+    #[test]
+    fn largest_component_of_three() {
+        let mut graph = graph_of(&[(0, 1), (1, 2), (3, 4), (4, 5), (5, 6),
+                                   (6, 7)]);
+        graph.add_node(Node::Int(8));
+        let largest = graph.largest_component().unwrap();
+        assert!(largest == graph_of(&[(3, 4), (4, 5), (5, 6), (6, 7)]));
+        assert_eq!(largest.node_count(), 5);
+    }
+
+    #[test]
+    fn largest_component_ties_and_empty_graph() {
+        let graph = graph_of(&[(0, 1), (2, 3)]);
+        assert!(graph.largest_component().unwrap() == graph_of(&[(0, 1)]));
+        assert!(Graph::<Node>::new().largest_component().is_none());
+    }
+
+    #[test]
+    fn betweenness_of_five_node_path() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        let scores = graph.betweenness_centrality();
+        assert_eq!(scores.len(), 5);
+        assert_eq!(scores[0], 0.0);
+        assert_eq!(scores[1], 3.0);
+        assert_eq!(scores[2], 4.0);
+        assert_eq!(scores[3], 3.0);
+        assert_eq!(scores[4], 0.0);
+    }
+
+    #[test]
+    fn betweenness_splits_between_shortest_paths() {
+        // 0 and 3 are joined by two shortest paths, one through each of 1, 2
+        let graph = graph_of(&[(0, 1), (0, 2), (1, 3), (2, 3)]);
+        let scores = graph.betweenness_centrality();
+        for i in range(0u, 4) {
+            assert_eq!(scores[i], 0.5);
+        }
+    }
+
+    #[test]
+    fn closeness_of_star() {
+        let mut graph = graph_of(&[(0, 1), (0, 2), (0, 3), (0, 4)]);
+        graph.add_node(Node::Int(5));
+        let scores = graph.closeness_centrality();
+        assert_eq!(scores[0], 1.0);
+        for i in range(1u, 5) {
+            assert_eq!(scores[i], 4.0 / 7.0);
+            assert!(scores[0] > scores[i]);
+        }
+        // The isolated node reaches nothing
+        assert_eq!(scores[5], 0.0);
+    }
+
+    #[test]
+    fn filter_nodes_above_threshold() {
+        let mut graph = graph_of(&[(1, 2), (2, 5), (5, 7), (1, 7)]);
+        graph.add_edge(&s("x"), &Node::Int(1));
+        graph.set_node_attr_value(&Node::Int(2), "colour".to_string(),
+                                  "red".to_string()).unwrap();
+        let small = graph.filter_nodes(|node: &Node| {
+            match *node {
+                Node::Int(v) => v <= 2,
+                Node::Str(_) => true,
+            }
+        });
+        assert_eq!(small.node_count(), 3);
+        assert_eq!(small.edge_count(), 2);
+        assert!(small.contains_edge(&Node::Int(1), &Node::Int(2)));
+        assert!(small.contains_edge(&Node::Int(1), &s("x")));
+        assert_eq!(small.get_node_attr_value(&Node::Int(2), "colour"),
+                   Some(&"red".to_string()));
+    }
+
+    #[test]
+    fn filter_edges_keeps_all_nodes() {
+        let graph = graph_of(&[(1, 2), (2, 3), (3, 4), (4, 1)]);
+        // Keep only the edges that don't touch 4
+        let kept = graph.filter_edges(|a: &Node, b: &Node| {
+            *a != Node::Int(4) && *b != Node::Int(4)
+        });
+        assert_eq!(kept.node_count(), 4);
+        assert_eq!(kept.edge_count(), 2);
+        assert!(kept.contains_edge(&Node::Int(1), &Node::Int(2)));
+        assert!(kept.contains_edge(&Node::Int(2), &Node::Int(3)));
+        assert_eq!(kept.degree(&Node::Int(4)), Ok(0));
+
+        let none = graph.filter_edges(|_: &Node, _: &Node| false);
+        assert_eq!(none.node_count(), 4);
+        assert_eq!(none.edge_count(), 0);
+    }
+
+    #[test]
+    fn adjacency_of_triangle() {
+        let mut graph = graph_of(&[(0, 1), (1, 2), (2, 0)]);
+        graph.add_node(Node::Int(3));
+        let adjacency = graph.adjacency();
+        assert_eq!(adjacency.len(), 4);
+        assert_eq!(adjacency[&Node::Int(0)],
+                   vec![&Node::Int(1), &Node::Int(2)]);
+        assert_eq!(adjacency[&Node::Int(1)],
+                   vec![&Node::Int(0), &Node::Int(2)]);
+        assert_eq!(adjacency[&Node::Int(2)],
+                   vec![&Node::Int(1), &Node::Int(0)]);
+        assert!(adjacency[&Node::Int(3)].is_empty());
+    }
+
+    #[test]
+    fn collect_pairs_into_graph() {
+        let pairs = vec![(s("a"), s("b")), (s("b"), s("c")), (s("c"), s("a")),
+                         (s("b"), s("a")), (s("c"), s("d"))];
+        let graph: Graph<Node> = pairs.into_iter().collect();
+        assert_eq!(graph.node_count(), 4);
+        // The repeated a-b pair is a single edge
+        assert_eq!(graph.edge_count(), 4);
+        assert!(!graph.has_multi_edges());
+        assert!(graph.contains_edge(&s("d"), &s("c")));
+    }
+
+    #[test]
+    fn extend_with_nodes() {
+        let mut graph = graph_of(&[(0, 1)]);
+        graph.extend(vec![Node::Int(1), Node::Int(2), s("x"), Node::Int(2)]
+                         .into_iter());
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.get_index(&s("x")), 3);
+        assert_eq!(graph.edge_count(), 1);
+    }
+
+    #[test]
+    fn extend_with_edges_keeps_existing_weights() {
+        let mut graph = Graph::new();
+        graph.add_weighted_edge(&s("a"), &s("b"), 2.5);
+        graph.extend(vec![(s("b"), s("a")), (s("b"), s("c")),
+                          (s("c"), s("b"))].into_iter());
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.edge_weight(&s("a"), &s("b")), Ok(2.5));
+        assert_eq!(graph.edge_weight(&s("c"), &s("b")), Ok(1.0));
+    }
+
+    #[test]
+    fn deduplicate_edges_collapses_repeats() {
+        let mut graph = graph_of(&[(0, 1), (1, 2)]);
+        assert!(!graph.has_multi_edges());
+
+        // add_edge won't make a duplicate, so push one in directly
+        graph.adj_list[0].push((1, 5.0));
+        graph.adj_list[1].push((0, 5.0));
+        assert!(graph.has_multi_edges());
+        assert_eq!(graph.edge_count(), 3);
+
+        graph.deduplicate_edges();
+        assert!(!graph.has_multi_edges());
+        assert_eq!(graph.edge_count(), 2);
+        // The first entry, and its weight, is the one kept
+        assert_eq!(graph.edge_weight(&Node::Int(0), &Node::Int(1)), Ok(1.0));
+    }
+
+    #[test]
+    fn self_loop_is_not_a_multi_edge() {
+        let mut graph = graph_of(&[(0, 1)]);
+        graph.allow_self_loops(true);
+        graph.add_edge(&Node::Int(1), &Node::Int(1));
+        assert!(!graph.has_multi_edges());
+        graph.deduplicate_edges();
+        assert_eq!(graph.edge_count(), 2);
+
+        graph.adj_list[1].push((1, 1.0));
+        assert!(graph.has_multi_edges());
+    }
+
+    #[test]
+    fn to_directed_and_back() {
+        let mut graph = graph_of(&[(0, 1), (1, 2)]);
+        graph.add_weighted_edge(&Node::Int(2), &Node::Int(3), 2.5);
+        graph.allow_self_loops(true);
+        graph.add_edge(&Node::Int(3), &Node::Int(3));
+        graph.set_name("g".to_string());
+
+        // Two directed edges per edge, but one for the self-loop
+        let directed = graph.to_directed();
+        assert_eq!(directed.edge_count(), 7);
+        assert_eq!(directed.name(), &"g".to_string());
+        assert_eq!(directed.in_degree(&Node::Int(1)), Ok(2));
+
+        let back = directed.to_undirected();
+        assert!(back == graph);
+        assert_eq!(back.edge_count(), 4);
+        assert_eq!(back.edge_weight(&Node::Int(3), &Node::Int(2)), Ok(2.5));
+        assert!(!back.allow_self_loops);
+    }
+
+    #[test]
+    fn merge_in_place_adds_missing_nodes_and_edges() {
+        let mut big = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        big.add_weighted_edge(&Node::Int(0), &Node::Int(4), 2.0);
+        big.set_node_attr_value(&Node::Int(1), "colour".to_string(),
+                                "red".to_string()).unwrap();
+
+        let mut small = Graph::new();
+        small.add_weighted_edge(&Node::Int(0), &Node::Int(4), 9.0);
+        small.add_edge(&Node::Int(1), &Node::Int(5));
+        small.allow_self_loops(true);
+        small.add_edge(&Node::Int(5), &Node::Int(5));
+        small.set_node_attr_value(&Node::Int(1), "colour".to_string(),
+                                  "blue".to_string()).unwrap();
+        small.set_node_attr_value(&Node::Int(5), "colour".to_string(),
+                                  "green".to_string()).unwrap();
+
+        big.merge_in_place(&small);
+        assert_eq!(big.node_count(), 6);
+        assert_eq!(big.edge_count(), 7);
+        // What big already had wins
+        assert_eq!(big.edge_weight(&Node::Int(4), &Node::Int(0)), Ok(2.0));
+        assert_eq!(big.get_node_attr_value(&Node::Int(1), "colour"),
+                   Some(&"red".to_string()));
+        assert_eq!(big.get_node_attr_value(&Node::Int(5), "colour"),
+                   Some(&"green".to_string()));
+        // The loop comes across, but big's setting stays as it was
+        assert_eq!(big.self_loops(), vec![&Node::Int(5)]);
+        assert!(!big.allow_self_loops);
+        assert_eq!(small.node_count(), 4);
+    }
+
+    #[test]
+    fn laplacian_of_three_node_path() {
+        let graph = graph_of(&[(0, 1), (1, 2)]);
+        assert_eq!(graph.laplacian_matrix(),
+                   vec![vec![1, -1, 0],
+                        vec![-1, 2, -1],
+                        vec![0, -1, 1]]);
+    }
+
+    #[test]
+    fn laplacian_leaves_out_self_loops() {
+        let mut graph = graph_of(&[(0, 1)]);
+        graph.allow_self_loops(true);
+        graph.add_edge(&Node::Int(1), &Node::Int(1));
+        assert_eq!(graph.laplacian_matrix(), vec![vec![1, -1], vec![-1, 1]]);
+    }
+
+    #[test]
+    fn incidence_matrix_of_triangle() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 0)]);
+        let matrix = graph.incidence_matrix();
+        // Columns in edges_iter order: 0-1, 0-2, 1-2
+        assert_eq!(matrix, vec![vec![1, 1, 0],
+                                vec![1, 0, 1],
+                                vec![0, 1, 1]]);
+        for column in range(0u, 3) {
+            let ones = matrix.iter().filter(|row| row[column] == 1).count();
+            assert_eq!(ones, 2);
+        }
+    }
+
+    #[test]
+    fn incidence_matrix_self_loop_column() {
+        let mut graph = graph_of(&[(0, 1)]);
+        graph.allow_self_loops(true);
+        graph.add_edge(&Node::Int(1), &Node::Int(1));
+        assert_eq!(graph.incidence_matrix(), vec![vec![1, 0], vec![1, 1]]);
+    }
+
+    #[test]
+    fn has_self_loop_per_node() {
+        let mut graph = graph_of(&[(0, 1), (1, 2)]);
+        graph.allow_self_loops(true);
+        graph.add_edge(&Node::Int(1), &Node::Int(1));
+        assert_eq!(graph.has_self_loop(&Node::Int(1)), Ok(true));
+        assert_eq!(graph.has_self_loop(&Node::Int(0)), Ok(false));
+        assert_eq!(graph.has_self_loop(&Node::Int(2)), Ok(false));
+        assert_eq!(graph.has_self_loop(&Node::Int(3)),
+                   Err(GraphError::NodeNotFound));
+
+        graph.remove_edge(&Node::Int(1), &Node::Int(1)).unwrap();
+        assert_eq!(graph.has_self_loop(&Node::Int(1)), Ok(false));
+        assert_eq!(graph.degree(&Node::Int(1)), Ok(2));
+    }
+
+    #[test]
+    fn node_label_of_both_variants() {
+        let mut graph = Graph::new();
+        graph.add_edge(&s("alpha"), &Node::Int(-12));
+        assert_eq!(graph.node_label(&s("alpha")), Some("alpha".to_string()));
+        assert_eq!(graph.node_label(&Node::Int(-12)), Some("-12".to_string()));
+        assert_eq!(graph.node_label(&s("beta")), None);
+        assert_eq!(graph.node_label(&Node::Int(0)), None);
+        // The node is only borrowed
+        assert!(graph.contains_node(&s("alpha")));
+    }
+
+    #[test]
+    fn to_csv_dimensions() {
+        let mut graph = graph_of(&[(0, 1), (1, 2)]);
+        graph.add_node(s("d"));
+        let csv = graph.to_csv();
+        let rows: Vec<&str> = csv.as_slice().lines().collect();
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[0], "0,1,2,d");
+        for row in rows.slice_from(1).iter() {
+            assert_eq!(row.split(',').count(), 4);
+        }
+        assert_eq!(rows.slice_from(1), ["0,1,0,0", "1,0,1,0", "0,1,0,0",
+                                        "0,0,0,0"].as_slice());
+    }
+
+    #[test]
+    fn to_csv_quotes_labels() {
+        let mut graph = Graph::new();
+        graph.add_edge(&s("a,b"), &s("say \"hi\""));
+        let csv = graph.to_csv();
+        assert!(csv.as_slice()
+                   .starts_with("\"a,b\",\"say \"\"hi\"\"\"\n0,1\n1,0\n"));
+    }
+
+    #[test]
+    fn maximum_matching_needs_an_augmenting_path() {
+        // Matching 0 with 10 first leaves 1 stuck until 0 moves over to 11
+        let graph = graph_of(&[(0, 10), (0, 11), (1, 10)]);
+        assert_eq!(graph.maximum_bipartite_matching(),
+                   Ok(vec![(&Node::Int(0), &Node::Int(11)),
+                           (&Node::Int(1), &Node::Int(10))]));
+    }
+
+    #[test]
+    fn maximum_matching_size() {
+        // Three on one side, two on the other: at most two pairs
+        let graph = graph_of(&[(0, 10), (1, 10), (1, 11), (2, 11), (2, 10)]);
+        let matching = graph.maximum_bipartite_matching().unwrap();
+        assert_eq!(matching.len(), 2);
+        let mut right = Vec::<&Node>::new();
+        for &(a, b) in matching.iter() {
+            assert!(graph.contains_edge(a, b));
+            assert!(!right.contains(&b));
+            right.push(b);
+        }
+
+        let cycle = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0)]);
+        assert_eq!(cycle.maximum_bipartite_matching().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn maximum_matching_of_odd_cycle() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 0)]);
+        assert_eq!(graph.maximum_bipartite_matching(),
+                   Err(GraphError::NotBipartite));
+    }
+
+    #[test]
+    fn maximal_cliques_of_k4() {
+        let graph = graph_of(&[(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+        assert_eq!(graph.maximal_cliques(),
+                   vec![vec![&Node::Int(0), &Node::Int(1), &Node::Int(2),
+                             &Node::Int(3)]]);
+    }
+
+    #[test]
+    fn maximal_cliques_of_triangles_sharing_a_node() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2)]);
+        let cliques = graph.maximal_cliques();
+        assert_eq!(cliques.len(), 2);
+        assert!(cliques.contains(&vec![&Node::Int(0), &Node::Int(1),
+                                       &Node::Int(2)]));
+        assert!(cliques.contains(&vec![&Node::Int(2), &Node::Int(3),
+                                       &Node::Int(4)]));
+    }
+
+    #[test]
+    fn maximal_cliques_of_path_and_isolated_node() {
+        let mut graph = graph_of(&[(0, 1), (1, 2)]);
+        graph.add_node(Node::Int(3));
+        let cliques = graph.maximal_cliques();
+        assert_eq!(cliques.len(), 3);
+        assert!(cliques.contains(&vec![&Node::Int(3)]));
+        assert!(Graph::<Node>::new().maximal_cliques().is_empty());
+    }
+
+    #[test]
+    fn girth_of_small_graphs() {
+        assert_eq!(graph_of(&[(0, 1), (1, 2), (2, 0)]).girth(), Some(3));
+        assert_eq!(graph_of(&[(0, 1), (1, 2), (2, 3), (3, 0)]).girth(),
+                   Some(4));
+        assert_eq!(graph_of(&[(0, 1), (1, 2), (1, 3)]).girth(), None);
+    }
+
+    #[test]
+    fn girth_finds_shortest_of_several_cycles() {
+        // A 5-cycle 0..4 sharing the edge 0-1 with the square 0-1-5-6
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0),
+                               (1, 5), (5, 6), (6, 0)]);
+        assert_eq!(graph.girth(), Some(4));
+
+        let mut looped = graph_of(&[(0, 1)]);
+        looped.allow_self_loops(true);
+        looped.add_edge(&Node::Int(1), &Node::Int(1));
+        assert_eq!(looped.girth(), Some(1));
+    }
+
+    #[test]
+    fn proper_tree() {
+        assert!(graph_of(&[(0, 1), (1, 2), (1, 3), (3, 4)]).is_tree());
+    }
+
+    #[test]
+    fn tree_plus_one_edge_is_not_tree() {
+        assert!(!graph_of(&[(0, 1), (1, 2), (1, 3), (3, 4), (4, 0)]).is_tree());
+    }
+
+    #[test]
+    fn forest_is_not_tree() {
+        assert!(!graph_of(&[(0, 1), (2, 3)]).is_tree());
+    }
+
+    #[test]
+    fn empty_and_single_node_trees() {
+        let mut graph = Graph::new();
+        assert!(!graph.is_tree());
+        graph.add_node(Node::Int(0));
+        assert!(graph.is_tree());
+        graph.allow_self_loops(true);
+        graph.add_edge(&Node::Int(0), &Node::Int(0));
+        assert!(!graph.is_tree());
+    }
+
+    #[test]
+    fn eccentricity_on_path() {
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        assert_eq!(graph.eccentricity(&Node::Int(0)), Ok(Some(4)));
+        assert_eq!(graph.eccentricity(&Node::Int(4)), Ok(Some(4)));
+        assert_eq!(graph.eccentricity(&Node::Int(1)), Ok(Some(3)));
+        assert_eq!(graph.eccentricity(&Node::Int(2)), Ok(Some(2)));
+    }
+
+    #[test]
+    fn eccentricity_with_unreachable_nodes() {
+        let mut graph = graph_of(&[(0, 1)]);
+        graph.add_node(Node::Int(2));
+        assert_eq!(graph.eccentricity(&Node::Int(0)), Ok(None));
+        assert_eq!(graph.eccentricity(&Node::Int(9)),
+                   Err(GraphError::NodeNotFound));
+    }
+
+    #[test]
+    fn center_of_odd_length_path() {
+        // Five nodes: the middle one alone
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        assert_eq!(graph.center(), vec![&Node::Int(2)]);
+    }
+
+    #[test]
+    fn center_of_even_length_path() {
+        // Four nodes: the two in the middle
+        let graph = graph_of(&[(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(graph.center(), vec![&Node::Int(1), &Node::Int(2)]);
+    }
+
+    #[test]
+    fn center_of_disconnected_graph() {
+        assert!(graph_of(&[(0, 1), (2, 3)]).center().is_empty());
+        assert!(Graph::<Node>::new().center().is_empty());
     }
 }
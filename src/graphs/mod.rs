@@ -1,4 +1,7 @@
 pub mod graph;
-//pub mod digraph;
+pub mod digraph;
+pub mod generators;
+mod dot;
+mod xml;
 //pub mod multigraph;
 //pub mod multidigraph;
@@ -0,0 +1,262 @@
+// Just enough of an XML reader for Graph::from_graphml: elements,
+// attributes and text. Comments, processing instructions and DOCTYPEs
+// are skipped, and namespace prefixes are left as part of the name.
+
+use std::collections::HashMap;
+use std::cell::Cell;
+use std::char;
+use std::num::from_str_radix;
+
+pub struct Element {
+    pub name: String,
+    pub attrs: HashMap<String, String>,
+    pub children: Vec<Element>,
+    // All text directly inside the element, entities already replaced
+    pub text: String,
+    // Line of the input the element starts on, counting from 1
+    pub line: uint,
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: uint,
+    // (position, line at that position) from the last call to line(), so
+    // each call only has to count the newlines since then
+    line_mark: Cell<(uint, uint)>,
+}
+
+pub fn parse(src: &str) -> Result<Element, uint> {
+    // Parse a whole document into its root element.
+    // On failure, gives the line of the input the problem was found on.
+    let mut parser = Parser { src: src, pos: 0, line_mark: Cell::new((0, 1)) };
+    try!(parser.skip_misc());
+    let root = try!(parser.element());
+    try!(parser.skip_misc());
+    if !parser.at_end() {
+        return Err(parser.line());
+    }
+    return Ok(root);
+}
+
+impl<'a> Parser<'a> {
+    fn element(&mut self) -> Result<Element, uint> {
+        // Parse one element, starting at its '<'
+        let line = self.line();
+        try!(self.expect("<"));
+        let name = try!(self.name());
+        let mut attrs = HashMap::<String, String>::new();
+
+        // Attributes, up to the end of the start tag
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("/>") {
+                self.pos += 2;
+                return Ok(Element { name: name, attrs: attrs,
+                                    children: Vec::new(),
+                                    text: String::new(), line: line });
+            }
+            if self.starts_with(">") {
+                self.pos += 1;
+                break;
+            }
+            let key = try!(self.name());
+            self.skip_whitespace();
+            try!(self.expect("="));
+            self.skip_whitespace();
+            let value = try!(self.quoted());
+            if attrs.contains_key(&key) {
+                return Err(self.line());
+            }
+            attrs.insert(key, value);
+        }
+
+        // Content, up to the matching end tag
+        let mut children = Vec::<Element>::new();
+        let mut text = String::new();
+        loop {
+            if self.at_end() {
+                return Err(self.line());
+            }
+            if self.starts_with("</") {
+                self.pos += 2;
+                let end_name = try!(self.name());
+                self.skip_whitespace();
+                try!(self.expect(">"));
+                if end_name != name {
+                    return Err(self.line());
+                }
+                break;
+            } else if self.starts_with("<!--") {
+                try!(self.skip_past("-->"));
+            } else if self.starts_with("<![CDATA[") {
+                self.pos += 9;
+                let start = self.pos;
+                try!(self.skip_past("]]>"));
+                text.push_str(self.src.slice(start, self.pos - 3));
+            } else if self.starts_with("<?") {
+                try!(self.skip_past("?>"));
+            } else if self.starts_with("<") {
+                children.push(try!(self.element()));
+            } else {
+                // Compare bytes rather than slicing, since the position
+                // may be in the middle of a multi-byte character
+                let start = self.pos;
+                while !self.at_end() && self.src.as_bytes()[self.pos] != b'<' {
+                    self.pos += 1;
+                }
+                match unescape(self.src.slice(start, self.pos)) {
+                    Some(s) => { text.push_str(s.as_slice()); }
+                    None => { return Err(self.line()); }
+                };
+            }
+        }
+        return Ok(Element { name: name, attrs: attrs, children: children,
+                            text: text, line: line });
+    }
+
+    fn name(&mut self) -> Result<String, uint> {
+        // Element or attribute name, up to whitespace or a delimiter
+        let start = self.pos;
+        while !self.at_end() {
+            let c = self.src.as_bytes()[self.pos];
+            if is_space(c) || c == b'=' || c == b'>' || c == b'/' ||
+               c == b'<' || c == b'"' || c == b'\'' {
+                break;
+            }
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(self.line());
+        }
+        return Ok(self.src.slice(start, self.pos).to_string());
+    }
+
+    fn quoted(&mut self) -> Result<String, uint> {
+        // Attribute value in single or double quotes
+        let quote = if self.starts_with("\"") {
+            "\""
+        } else if self.starts_with("'") {
+            "'"
+        } else {
+            return Err(self.line());
+        };
+        self.pos += 1;
+        let start = self.pos;
+        try!(self.skip_past(quote));
+        match unescape(self.src.slice(start, self.pos - 1)) {
+            Some(s) => Ok(s),
+            None => Err(self.line()),
+        }
+    }
+
+    fn skip_misc(&mut self) -> Result<(), uint> {
+        // Skip whitespace, comments, the XML declaration and DOCTYPE
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("<?") {
+                try!(self.skip_past("?>"));
+            } else if self.starts_with("<!--") {
+                try!(self.skip_past("-->"));
+            } else if self.starts_with("<!") {
+                try!(self.skip_past(">"));
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while !self.at_end() && is_space(self.src.as_bytes()[self.pos]) {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_past(&mut self, pat: &str) -> Result<(), uint> {
+        // Move to just after the next occurrence of `pat`
+        match self.src.slice_from(self.pos).find_str(pat) {
+            Some(offset) => {
+                self.pos += offset + pat.len();
+                return Ok(());
+            }
+            None => { return Err(self.line()); }
+        };
+    }
+
+    fn expect(&mut self, pat: &str) -> Result<(), uint> {
+        if !self.starts_with(pat) {
+            return Err(self.line());
+        }
+        self.pos += pat.len();
+        return Ok(());
+    }
+
+    fn starts_with(&self, pat: &str) -> bool {
+        return self.src.slice_from(self.pos).starts_with(pat);
+    }
+
+    fn at_end(&self) -> bool {
+        return self.pos >= self.src.len();
+    }
+
+    fn line(&self) -> uint {
+        // Line of the current position, counting from 1.
+        // The position only moves forward, so start from the last mark.
+        let end = if self.at_end() { self.src.len() } else { self.pos };
+        let (mark, line) = self.line_mark.get();
+        let newlines = self.src.slice(mark, end).bytes()
+                           .filter(|&c| c == b'\n').count();
+        self.line_mark.set((end, line + newlines));
+        return line + newlines;
+    }
+}
+
+fn is_space(c: u8) -> bool {
+    // XML whitespace. Only ASCII bytes count, so a multi-byte character is
+    // never split.
+    return c == b' ' || c == b'\t' || c == b'\n' || c == b'\r';
+}
+
+fn unescape(s: &str) -> Option<String> {
+    // Replace the predefined entities and character references.
+    // None for an unknown or unterminated entity.
+    let mut out = String::new();
+    let mut rest = s;
+    loop {
+        let amp = match rest.find('&') {
+            Some(x) => x,
+            None => break,
+        };
+        out.push_str(rest.slice_to(amp));
+        let semi = match rest.slice_from(amp).find(';') {
+            Some(x) => amp + x,
+            None => { return None; }
+        };
+        let entity = rest.slice(amp + 1, semi);
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ if entity.starts_with("#x") => {
+                match from_str_radix::<u32>(entity.slice_from(2), 16)
+                        .and_then(char::from_u32) {
+                    Some(c) => c,
+                    None => { return None; }
+                }
+            }
+            _ if entity.starts_with("#") => {
+                match from_str::<u32>(entity.slice_from(1))
+                        .and_then(char::from_u32) {
+                    Some(c) => c,
+                    None => { return None; }
+                }
+            }
+            _ => { return None; }
+        };
+        out.push(c);
+        rest = rest.slice_from(semi + 1);
+    }
+    out.push_str(rest);
+    return Some(out);
+}
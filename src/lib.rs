@@ -1,3 +1,7 @@
+extern crate serialize;
+#[cfg(test)]
+extern crate time;
+
 // mod algorithms;
 // mod converters;
 // mod exceptions;